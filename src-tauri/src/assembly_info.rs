@@ -0,0 +1,324 @@
+// src-tauri/src/assembly_info.rs
+
+//! Reads the assembly name and version from a .NET DLL's metadata (ECMA-335), without
+//! loading it. Only the tables in front of the `Assembly` table are sized, never parsed.
+
+use serde::{Deserialize, Serialize};
+use std::{fs, path::Path};
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct AssemblyIdentity {
+    pub name: String,
+    /// `Major.Minor.Build.Revision`, the version the runtime binds against.
+    pub version: String,
+}
+
+const ASSEMBLY_TABLE: usize = 0x20;
+
+// Coded index families: the tag width and the tables they can point into.
+const TYPE_DEF_OR_REF: (u32, &[usize]) = (2, &[0x02, 0x01, 0x1B]);
+const HAS_CONSTANT: (u32, &[usize]) = (2, &[0x04, 0x08, 0x17]);
+const HAS_CUSTOM_ATTRIBUTE: (u32, &[usize]) = (
+    5,
+    &[
+        0x06, 0x04, 0x01, 0x02, 0x08, 0x09, 0x0A, 0x00, 0x0E, 0x17, 0x14, 0x11, 0x1A, 0x1B, 0x20,
+        0x23, 0x26, 0x27, 0x28, 0x2A, 0x2C, 0x2B,
+    ],
+);
+const HAS_FIELD_MARSHAL: (u32, &[usize]) = (1, &[0x04, 0x08]);
+const HAS_DECL_SECURITY: (u32, &[usize]) = (2, &[0x02, 0x06, 0x20]);
+const MEMBER_REF_PARENT: (u32, &[usize]) = (3, &[0x02, 0x01, 0x1A, 0x06, 0x1B]);
+const HAS_SEMANTICS: (u32, &[usize]) = (1, &[0x14, 0x17]);
+const METHOD_DEF_OR_REF: (u32, &[usize]) = (1, &[0x06, 0x0A]);
+const MEMBER_FORWARDED: (u32, &[usize]) = (1, &[0x04, 0x06]);
+const RESOLUTION_SCOPE: (u32, &[usize]) = (2, &[0x00, 0x1A, 0x23, 0x01]);
+const CUSTOM_ATTRIBUTE_TYPE: (u32, &[usize]) = (3, &[0x06, 0x0A]);
+
+/// A column in a metadata table row.
+enum Col {
+    Fixed(usize),
+    Str,
+    Guid,
+    Blob,
+    Table(usize),
+    Coded((u32, &'static [usize])),
+}
+
+/// Columns of tables 0x00..=0x1F, which precede `Assembly`.
+fn table_schema(table: usize) -> &'static [Col] {
+    use Col::*;
+    match table {
+        0x00 => &[Fixed(2), Str, Guid, Guid, Guid],
+        0x01 => &[Coded(RESOLUTION_SCOPE), Str, Str],
+        0x02 => &[
+            Fixed(4),
+            Str,
+            Str,
+            Coded(TYPE_DEF_OR_REF),
+            Table(0x04),
+            Table(0x06),
+        ],
+        0x03 => &[Table(0x04)],
+        0x04 => &[Fixed(2), Str, Blob],
+        0x05 => &[Table(0x06)],
+        0x06 => &[Fixed(4), Fixed(2), Fixed(2), Str, Blob, Table(0x08)],
+        0x07 => &[Table(0x08)],
+        0x08 => &[Fixed(2), Fixed(2), Str],
+        0x09 => &[Table(0x02), Coded(TYPE_DEF_OR_REF)],
+        0x0A => &[Coded(MEMBER_REF_PARENT), Str, Blob],
+        0x0B => &[Fixed(2), Coded(HAS_CONSTANT), Blob],
+        0x0C => &[
+            Coded(HAS_CUSTOM_ATTRIBUTE),
+            Coded(CUSTOM_ATTRIBUTE_TYPE),
+            Blob,
+        ],
+        0x0D => &[Coded(HAS_FIELD_MARSHAL), Blob],
+        0x0E => &[Fixed(2), Coded(HAS_DECL_SECURITY), Blob],
+        0x0F => &[Fixed(2), Fixed(4), Table(0x02)],
+        0x10 => &[Fixed(4), Table(0x04)],
+        0x11 => &[Blob],
+        0x12 => &[Table(0x02), Table(0x14)],
+        0x13 => &[Table(0x14)],
+        0x14 => &[Fixed(2), Str, Coded(TYPE_DEF_OR_REF)],
+        0x15 => &[Table(0x02), Table(0x17)],
+        0x16 => &[Table(0x17)],
+        0x17 => &[Fixed(2), Str, Blob],
+        0x18 => &[Fixed(2), Table(0x06), Coded(HAS_SEMANTICS)],
+        0x19 => &[
+            Table(0x02),
+            Coded(METHOD_DEF_OR_REF),
+            Coded(METHOD_DEF_OR_REF),
+        ],
+        0x1A => &[Str],
+        0x1B => &[Blob],
+        0x1C => &[Fixed(2), Coded(MEMBER_FORWARDED), Str, Table(0x1A)],
+        0x1D => &[Fixed(4), Table(0x04)],
+        0x1E => &[Fixed(4), Fixed(4)],
+        0x1F => &[Fixed(4)],
+        _ => &[],
+    }
+}
+
+fn u16_at(bytes: &[u8], offset: usize) -> Option<u16> {
+    Some(u16::from_le_bytes(
+        bytes.get(offset..offset + 2)?.try_into().ok()?,
+    ))
+}
+
+fn u32_at(bytes: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(
+        bytes.get(offset..offset + 4)?.try_into().ok()?,
+    ))
+}
+
+fn u64_at(bytes: &[u8], offset: usize) -> Option<u64> {
+    Some(u64::from_le_bytes(
+        bytes.get(offset..offset + 8)?.try_into().ok()?,
+    ))
+}
+
+/// Reads a 2 or 4 byte index.
+fn index_at(bytes: &[u8], offset: usize, width: usize) -> Option<u32> {
+    match width {
+        2 => u16_at(bytes, offset).map(u32::from),
+        _ => u32_at(bytes, offset),
+    }
+}
+
+fn c_str_at(bytes: &[u8], offset: usize) -> Option<&str> {
+    let tail = bytes.get(offset..)?;
+    let end = tail.iter().position(|&b| b == 0)?;
+    std::str::from_utf8(&tail[..end]).ok()
+}
+
+/// Maps a relative virtual address to a file offset through the section table.
+fn rva_to_offset(bytes: &[u8], sections: usize, count: usize, rva: u32) -> Option<usize> {
+    (0..count).find_map(|i| {
+        let header = sections + i * 40;
+        let virtual_size = u32_at(bytes, header + 8)?;
+        let virtual_address = u32_at(bytes, header + 12)?;
+        let raw_size = u32_at(bytes, header + 16)?;
+        let raw_pointer = u32_at(bytes, header + 20)?;
+        let size = virtual_size.max(raw_size);
+        let offset = rva.checked_sub(virtual_address).filter(|&o| o < size)?;
+        offset.checked_add(raw_pointer).map(|o| o as usize)
+    })
+}
+
+/// File offset of the CLI metadata root, or `None` for native DLLs.
+fn metadata_root(bytes: &[u8]) -> Option<usize> {
+    if bytes.get(0..2)? != b"MZ" {
+        return None;
+    }
+    let pe = u32_at(bytes, 0x3C)? as usize;
+    if bytes.get(pe..pe + 4)? != b"PE\0\0" {
+        return None;
+    }
+    let coff = pe + 4;
+    let section_count = u16_at(bytes, coff + 2)? as usize;
+    let optional_size = u16_at(bytes, coff + 16)? as usize;
+    let optional = coff + 20;
+    let directories = match u16_at(bytes, optional)? {
+        0x10b => optional + 96,
+        0x20b => optional + 112,
+        _ => return None,
+    };
+    let sections = optional + optional_size;
+    // Data directory 14 is the CLR runtime header.
+    let cli_rva = u32_at(bytes, directories + 14 * 8)?;
+    if cli_rva == 0 {
+        return None;
+    }
+    let cli = rva_to_offset(bytes, sections, section_count, cli_rva)?;
+    let metadata_rva = u32_at(bytes, cli + 8)?;
+    let root = rva_to_offset(bytes, sections, section_count, metadata_rva)?;
+    (u32_at(bytes, root)? == 0x424A_5342).then_some(root)
+}
+
+/// Parses assembly metadata from a DLL's bytes.
+pub fn parse_assembly_identity(bytes: &[u8]) -> Option<AssemblyIdentity> {
+    let root = metadata_root(bytes)?;
+    let version_length = u32_at(bytes, root + 12)? as usize;
+    let stream_count = u16_at(bytes, root + 16 + version_length + 2)? as usize;
+
+    let mut tables_stream = None;
+    let mut strings_stream = None;
+    let mut header = root + 16 + version_length + 4;
+    for _ in 0..stream_count {
+        let offset = root + u32_at(bytes, header)? as usize;
+        let name = c_str_at(bytes, header + 8)?;
+        match name {
+            "#~" | "#-" => tables_stream = Some(offset),
+            "#Strings" => strings_stream = Some(offset),
+            _ => {}
+        }
+        // Names are null-terminated and padded to four bytes.
+        header += 8 + (name.len() + 4) / 4 * 4;
+    }
+    let tables = tables_stream?;
+    let strings = strings_stream?;
+
+    let heap_sizes = *bytes.get(tables + 6)?;
+    let valid = u64_at(bytes, tables + 8)?;
+    if valid & (1 << ASSEMBLY_TABLE) == 0 {
+        return None;
+    }
+    let mut rows = [0u32; 64];
+    let mut cursor = tables + 24;
+    for (table, count) in rows.iter_mut().enumerate() {
+        if valid & (1 << table) != 0 {
+            *count = u32_at(bytes, cursor)?;
+            cursor += 4;
+        }
+    }
+    // Some compilers append four extra bytes after the row counts.
+    if heap_sizes & 0x40 != 0 {
+        cursor += 4;
+    }
+
+    let str_width = if heap_sizes & 0x01 != 0 { 4 } else { 2 };
+    let guid_width = if heap_sizes & 0x02 != 0 { 4 } else { 2 };
+    let blob_width = if heap_sizes & 0x04 != 0 { 4 } else { 2 };
+    let column_width = |col: &Col| -> usize {
+        match col {
+            Col::Fixed(n) => *n,
+            Col::Str => str_width,
+            Col::Guid => guid_width,
+            Col::Blob => blob_width,
+            Col::Table(t) => {
+                if rows[*t] < 1 << 16 {
+                    2
+                } else {
+                    4
+                }
+            }
+            Col::Coded((tag_bits, targets)) => {
+                let max = targets.iter().map(|t| rows[*t]).max().unwrap_or(0);
+                if max < 1 << (16 - tag_bits) {
+                    2
+                } else {
+                    4
+                }
+            }
+        }
+    };
+
+    for (table, count) in rows.iter().enumerate().take(ASSEMBLY_TABLE) {
+        let row_size: usize = table_schema(table).iter().map(&column_width).sum();
+        cursor += row_size * *count as usize;
+    }
+
+    // Assembly: HashAlgId u32, Major/Minor/Build/Revision u16, Flags u32, PublicKey blob,
+    // Name string, Culture string.
+    let major = u16_at(bytes, cursor + 4)?;
+    let minor = u16_at(bytes, cursor + 6)?;
+    let build = u16_at(bytes, cursor + 8)?;
+    let revision = u16_at(bytes, cursor + 10)?;
+    let name_index = index_at(bytes, cursor + 16 + blob_width, str_width)? as usize;
+    let name = c_str_at(bytes, strings + name_index)?;
+    if name.is_empty() {
+        return None;
+    }
+    Some(AssemblyIdentity {
+        name: name.to_string(),
+        version: format!("{}.{}.{}.{}", major, minor, build, revision),
+    })
+}
+
+/// Reads a DLL's assembly identity; `None` for native or unreadable files.
+pub fn read_assembly_identity(path: &Path) -> Option<AssemblyIdentity> {
+    parse_assembly_identity(&fs::read(path).ok()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A minimal PE32 assembly `XLFixture` 1.2.3.4, with Module, TypeRef and TypeDef rows in
+    /// front of its Assembly row.
+    const MANAGED: &[u8] = include_bytes!("../tests/fixtures/managed.dll");
+    /// The same image without a CLR header.
+    const NATIVE: &[u8] = include_bytes!("../tests/fixtures/native.dll");
+
+    #[test]
+    fn reads_name_and_version_from_a_managed_dll() {
+        assert_eq!(
+            parse_assembly_identity(MANAGED),
+            Some(AssemblyIdentity {
+                name: "XLFixture".into(),
+                version: "1.2.3.4".into(),
+            })
+        );
+    }
+
+    #[test]
+    fn native_dlls_have_no_identity() {
+        assert_eq!(parse_assembly_identity(NATIVE), None);
+        assert_eq!(parse_assembly_identity(b"MZ"), None);
+        assert_eq!(parse_assembly_identity(b""), None);
+    }
+
+    #[test]
+    fn truncated_input_never_yields_a_wrong_identity() {
+        let full = parse_assembly_identity(MANAGED);
+        for len in 0..MANAGED.len() {
+            let parsed = parse_assembly_identity(&MANAGED[..len]);
+            assert!(parsed.is_none() || parsed == full, "cut at {}", len);
+        }
+        // Cut inside the metadata, before the Assembly row.
+        assert_eq!(parse_assembly_identity(&MANAGED[..0x2a0]), None);
+    }
+
+    #[test]
+    fn section_lookup_does_not_overflow() {
+        let mut bytes = MANAGED.to_vec();
+        let sections = 0x40 + 4 + 20 + 224;
+        // VirtualAddress and PointerToRawData near u32::MAX.
+        bytes[sections + 12..sections + 16].copy_from_slice(&0xFFFF_FF00u32.to_le_bytes());
+        bytes[sections + 20..sections + 24].copy_from_slice(&0xFFFF_FFF0u32.to_le_bytes());
+        assert_eq!(rva_to_offset(&bytes, sections, 1, 0xFFFF_FF20), None);
+        assert_eq!(rva_to_offset(&bytes, sections, 1, 0x10), None);
+        assert_eq!(parse_assembly_identity(&bytes), None);
+    }
+}
@@ -0,0 +1,341 @@
+// src-tauri/src/backups.rs
+
+//! The `_backup` folders left behind when a content folder is replaced by a link.
+//! They can be listed, restored as the real folder, merged into the current target or trashed.
+
+use crate::content_folders::{backup_dirs, folder_path, inspect, ContentFolder, ContentFolderStatus};
+use crate::error::{CommandError, CommandResult};
+use crate::link_backend::{remove_link, LinkBackend, PlatformLinkBackend};
+use crate::relocate::copy_verified;
+use crate::utils::system_time_to_millis;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+use tauri::{command, AppHandle};
+use walkdir::WalkDir;
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupEntry {
+    pub kind: ContentFolder,
+    pub name: String,
+    pub path: PathBuf,
+    pub size_bytes: u64,
+    pub file_count: u64,
+    /// Modification time in milliseconds since the epoch.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub modified: Option<u64>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupMergeResult {
+    pub target: PathBuf,
+    pub merged: Vec<String>,
+    /// Entries left in the backup because the target already has one with that name.
+    pub skipped: Vec<String>,
+    pub backup_removed: bool,
+}
+
+fn describe(kind: ContentFolder, path: PathBuf) -> BackupEntry {
+    let (file_count, size_bytes) = WalkDir::new(&path)
+        .min_depth(1)
+        .into_iter()
+        .filter_map(Result::ok)
+        .filter(|e| e.file_type().is_file())
+        .fold((0, 0), |(c, s), e| {
+            (c + 1, s + e.metadata().map(|m| m.len()).unwrap_or(0))
+        });
+    BackupEntry {
+        kind,
+        name: path
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default(),
+        modified: system_time_to_millis(fs::metadata(&path).and_then(|m| m.modified()).ok()),
+        path,
+        size_bytes,
+        file_count,
+    }
+}
+
+/// Only paths that really are backups of `folder` may be restored, merged or trashed.
+fn resolve_backup(folder: &Path, backup_path: &str) -> CommandResult<PathBuf> {
+    let requested = PathBuf::from(backup_path);
+    backup_dirs(folder)
+        .into_iter()
+        .find(|b| *b == requested)
+        .ok_or_else(|| {
+            CommandError::Input(format!(
+                "{} is not a backup of {}",
+                backup_path,
+                folder.display()
+            ))
+        })
+}
+
+/// Moves `from` to `to`, copying and deleting when a rename crosses volumes.
+/// The source is only deleted once the copy has been verified.
+fn move_entry(from: &Path, to: &Path) -> CommandResult<()> {
+    if fs::rename(from, to).is_ok() {
+        return Ok(());
+    }
+    copy_verified(from, to)?;
+    if fs::symlink_metadata(from)?.is_dir() {
+        fs::remove_dir_all(from)?;
+    } else {
+        fs::remove_file(from)?;
+    }
+    Ok(())
+}
+
+/// Puts `backup` in place of the link at `folder`. The link is renamed aside first and
+/// put back if the backup cannot be moved in; it is only removed once the swap is done.
+pub(crate) fn swap_in_backup(backend: &impl LinkBackend, folder: &Path, backup: &Path) -> CommandResult<()> {
+    let is_link = backend.is_link(folder)?;
+    if !is_link && fs::symlink_metadata(folder).is_ok() {
+        return Err(CommandError::Input(format!(
+            "{} is a regular folder; trash or merge the backup instead",
+            folder.display()
+        )));
+    }
+    let aside = is_link.then(|| {
+        let name = folder.file_name().unwrap_or_default().to_string_lossy();
+        folder.with_file_name(format!(".{}-{}", name, uuid::Uuid::new_v4()))
+    });
+    if let Some(aside) = &aside {
+        fs::rename(folder, aside).map_err(|e| {
+            CommandError::Io(format!(
+                "Failed to move link {} aside: {}",
+                folder.display(),
+                e
+            ))
+        })?;
+    }
+    if let Err(e) = fs::rename(backup, folder) {
+        if let Some(aside) = &aside {
+            if let Err(undo) = fs::rename(aside, folder) {
+                log::error!(
+                    "[backups::restore] Could not put link {} back: {}",
+                    folder.display(),
+                    undo
+                );
+            }
+        }
+        return Err(CommandError::Io(format!(
+            "Failed to move {} to {}: {}",
+            backup.display(),
+            folder.display(),
+            e
+        )));
+    }
+    if let Some(aside) = aside {
+        if let Err(e) = remove_link(backend, &aside) {
+            log::warn!(
+                "[backups::restore] Could not remove old link {}: {}",
+                aside.display(),
+                e
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Lists backups of a content folder, newest first.
+#[command]
+pub fn list_content_backups(
+    app_handle: AppHandle,
+    kind: ContentFolder,
+    root: Option<String>,
+) -> CommandResult<Vec<BackupEntry>> {
+    let folder = folder_path(&app_handle, kind, root)?;
+    let mut backups: Vec<BackupEntry> = backup_dirs(&folder)
+        .into_iter()
+        .map(|p| describe(kind, p))
+        .collect();
+    backups.sort_by_key(|b| std::cmp::Reverse(b.modified));
+    Ok(backups)
+}
+
+/// Removes the link and puts the chosen backup back as the real folder.
+#[command]
+pub fn restore_content_backup(
+    app_handle: AppHandle,
+    kind: ContentFolder,
+    backup_path: String,
+    root: Option<String>,
+) -> CommandResult<ContentFolderStatus> {
+    let folder = folder_path(&app_handle, kind, root)?;
+    let backup = resolve_backup(&folder, &backup_path)?;
+    log::info!(
+        "[backups::restore] {} -> {}",
+        backup.display(),
+        folder.display()
+    );
+    swap_in_backup(&PlatformLinkBackend, &folder, &backup)?;
+    Ok(inspect(&PlatformLinkBackend, kind, &folder))
+}
+
+/// Moves the backup's entries into whatever the content folder currently points at.
+/// Name clashes are left in the backup; the backup is removed once it is empty.
+#[command]
+pub fn merge_content_backup(
+    app_handle: AppHandle,
+    kind: ContentFolder,
+    backup_path: String,
+    root: Option<String>,
+) -> CommandResult<BackupMergeResult> {
+    let folder = folder_path(&app_handle, kind, root)?;
+    let backup = resolve_backup(&folder, &backup_path)?;
+    let target = if PlatformLinkBackend.is_link(&folder)? {
+        let t = PlatformLinkBackend.read_link(&folder)?;
+        match folder.parent() {
+            Some(parent) if t.is_relative() => parent.join(t),
+            _ => t,
+        }
+    } else {
+        folder.clone()
+    };
+    if !target.is_dir() {
+        return Err(CommandError::Input(format!(
+            "Merge target is not reachable: {}",
+            target.display()
+        )));
+    }
+
+    let mut merged = Vec::new();
+    let mut skipped = Vec::new();
+    for entry in fs::read_dir(&backup)?.filter_map(Result::ok) {
+        let name = entry.file_name().to_string_lossy().into_owned();
+        let dest = target.join(entry.file_name());
+        if fs::symlink_metadata(&dest).is_ok() {
+            skipped.push(name);
+            continue;
+        }
+        move_entry(&entry.path(), &dest)?;
+        merged.push(name);
+    }
+
+    let backup_removed = skipped.is_empty() && fs::remove_dir(&backup).is_ok();
+    log::info!(
+        "[backups::merge] {} -> {}: {} merged, {} skipped",
+        backup.display(),
+        target.display(),
+        merged.len(),
+        skipped.len()
+    );
+    Ok(BackupMergeResult {
+        target,
+        merged,
+        skipped,
+        backup_removed,
+    })
+}
+
+#[command]
+pub fn trash_content_backup(
+    app_handle: AppHandle,
+    kind: ContentFolder,
+    backup_path: String,
+    root: Option<String>,
+) -> CommandResult<()> {
+    let folder = folder_path(&app_handle, kind, root)?;
+    let backup = resolve_backup(&folder, &backup_path)?;
+    log::info!("[backups::trash] {}", backup.display());
+    trash::delete(&backup)?;
+    Ok(())
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    fn temp_dir() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("xlfm-backups-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn swap_replaces_the_link_with_the_backup() {
+        let root = temp_dir();
+        let (target, folder, backup) = (
+            root.join("Moved"),
+            root.join("Maps"),
+            root.join("Maps_backup"),
+        );
+        fs::create_dir_all(&target).unwrap();
+        fs::create_dir_all(backup.join("Park")).unwrap();
+        std::os::unix::fs::symlink(&target, &folder).unwrap();
+
+        swap_in_backup(&PlatformLinkBackend, &folder, &backup).unwrap();
+        assert!(!PlatformLinkBackend.is_link(&folder).unwrap());
+        assert!(folder.join("Park").is_dir());
+        assert!(target.is_dir());
+        assert_eq!(fs::read_dir(&root).unwrap().count(), 2);
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn failed_swap_puts_the_link_back() {
+        let root = temp_dir();
+        let (target, folder) = (root.join("Moved"), root.join("Maps"));
+        fs::create_dir_all(&target).unwrap();
+        std::os::unix::fs::symlink(&target, &folder).unwrap();
+
+        let missing = root.join("Maps_backup");
+        assert!(swap_in_backup(&PlatformLinkBackend, &folder, &missing).is_err());
+        assert_eq!(PlatformLinkBackend.read_link(&folder).unwrap(), target);
+        assert_eq!(fs::read_dir(&root).unwrap().count(), 2);
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn swap_refuses_a_real_folder() {
+        let root = temp_dir();
+        let (folder, backup) = (root.join("Maps"), root.join("Maps_backup"));
+        fs::create_dir_all(&folder).unwrap();
+        fs::create_dir_all(&backup).unwrap();
+
+        assert!(matches!(
+            swap_in_backup(&PlatformLinkBackend, &folder, &backup),
+            Err(CommandError::Input(_))
+        ));
+        assert!(backup.is_dir());
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn move_entry_moves_a_tree() {
+        let root = temp_dir();
+        let from = root.join("Park");
+        fs::create_dir_all(from.join("textures")).unwrap();
+        fs::write(from.join("textures").join("a.png"), "png").unwrap();
+        let to = root.join("Target").join("Park");
+        fs::create_dir_all(to.parent().unwrap()).unwrap();
+
+        move_entry(&from, &to).unwrap();
+        assert_eq!(
+            fs::read_to_string(to.join("textures").join("a.png")).unwrap(),
+            "png"
+        );
+        assert!(!from.exists());
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn copy_verified_copies_and_checks_a_tree() {
+        let root = temp_dir();
+        let from = root.join("Park");
+        fs::create_dir_all(from.join("textures")).unwrap();
+        fs::write(from.join("park_bundle"), "bundle").unwrap();
+        fs::write(from.join("textures").join("a.png"), "png").unwrap();
+
+        assert_eq!(copy_verified(&from, &root.join("Copy")).unwrap(), 2);
+        assert!(root.join("Copy").join("textures").join("a.png").is_file());
+        assert!(copy_verified(&from, &root.join("Copy")).is_err());
+        fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -0,0 +1,788 @@
+// src-tauri/src/bundle_preview.rs
+
+//! Pulls a preview image out of a Unity asset bundle (`UnityFS`). Maps that ship without a
+//! loose PNG often carry a `Texture2D` named like `preview` or `thumbnail`; this reads the
+//! bundle's serialized files, finds that texture and decodes its top mip. Only the blocks
+//! holding the needed bytes are decompressed, one at a time.
+
+use image::{DynamicImage, RgbaImage};
+use lzma_rs::decompress::{Options as LzmaOptions, UnpackedSize};
+use std::{
+    fs::File,
+    io::{Read, Seek, SeekFrom},
+    path::Path,
+};
+
+const SIGNATURE: &[u8] = b"UnityFS\0";
+/// Texture names, lowercased, that mark a preview image.
+const PREVIEW_HINTS: [&str; 4] = ["preview", "thumbnail", "thumb", "cover"];
+const TEXTURE_2D: i32 = 28;
+/// Blocks, block tables and reads above this are skipped rather than loaded into memory.
+const MAX_BLOCK_BYTES: u32 = 512 * 1024 * 1024;
+/// Larger textures are not previews; this also bounds the decoded image to 256 MiB.
+const MAX_TEXTURE_SIZE: u32 = 8192;
+
+/// Whether `path` starts with the `UnityFS` signature.
+pub fn is_asset_bundle(path: &Path) -> bool {
+    let mut signature = [0u8; 8];
+    File::open(path)
+        .and_then(|mut file| file.read_exact(&mut signature))
+        .is_ok()
+        && signature == SIGNATURE
+}
+
+/// Bounds-checked reads over a byte slice in either byte order.
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+    big_endian: bool,
+}
+
+impl<'a> Reader<'a> {
+    fn new(bytes: &'a [u8], big_endian: bool) -> Self {
+        Self {
+            bytes,
+            pos: 0,
+            big_endian,
+        }
+    }
+
+    fn take(&mut self, len: usize) -> Option<&'a [u8]> {
+        let slice = self.bytes.get(self.pos..self.pos.checked_add(len)?)?;
+        self.pos += len;
+        Some(slice)
+    }
+
+    fn skip(&mut self, len: usize) -> Option<()> {
+        self.take(len).map(|_| ())
+    }
+
+    fn align(&mut self, to: usize) {
+        self.pos = self.pos.div_ceil(to) * to;
+    }
+
+    fn array<const N: usize>(&mut self) -> Option<[u8; N]> {
+        self.take(N)?.try_into().ok()
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        Some(self.take(1)?[0])
+    }
+
+    fn u16(&mut self) -> Option<u16> {
+        let b = self.array()?;
+        Some(if self.big_endian {
+            u16::from_be_bytes(b)
+        } else {
+            u16::from_le_bytes(b)
+        })
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        let b = self.array()?;
+        Some(if self.big_endian {
+            u32::from_be_bytes(b)
+        } else {
+            u32::from_le_bytes(b)
+        })
+    }
+
+    fn u64(&mut self) -> Option<u64> {
+        let b = self.array()?;
+        Some(if self.big_endian {
+            u64::from_be_bytes(b)
+        } else {
+            u64::from_le_bytes(b)
+        })
+    }
+
+    fn i32(&mut self) -> Option<i32> {
+        self.u32().map(|v| v as i32)
+    }
+
+    fn c_str(&mut self) -> Option<String> {
+        let tail = self.bytes.get(self.pos..)?;
+        let end = tail.iter().position(|&b| b == 0)?;
+        self.pos += end + 1;
+        Some(String::from_utf8_lossy(&tail[..end]).into_owned())
+    }
+
+    /// A length-prefixed string padded to four bytes.
+    fn aligned_string(&mut self) -> Option<String> {
+        let len = self.u32()? as usize;
+        let text = String::from_utf8_lossy(self.take(len)?).into_owned();
+        self.align(4);
+        Some(text)
+    }
+}
+
+fn decompress(data: &[u8], flags: u32, size: usize) -> Option<Vec<u8>> {
+    match flags & 0x3F {
+        0 => Some(data.to_vec()),
+        // Unity writes the LZMA properties without the usual size field.
+        1 => {
+            let mut out = Vec::with_capacity(size);
+            let options = LzmaOptions {
+                unpacked_size: UnpackedSize::UseProvided(Some(size as u64)),
+                ..Default::default()
+            };
+            lzma_rs::lzma_decompress_with_options(&mut &data[..], &mut out, &options).ok()?;
+            Some(out)
+        }
+        2 | 3 => lz4_flex::block::decompress(data, size).ok(),
+        _ => None,
+    }
+}
+
+struct Block {
+    file_offset: u64,
+    data_offset: u64,
+    compressed_size: u32,
+    size: u32,
+    flags: u16,
+}
+
+struct Node {
+    offset: u64,
+    size: u64,
+    path: String,
+}
+
+struct Bundle {
+    file: File,
+    file_len: u64,
+    blocks: Vec<Block>,
+    nodes: Vec<Node>,
+    /// The most recently decompressed block, kept for reads that continue into it.
+    cached: Option<(usize, Vec<u8>)>,
+}
+
+impl Bundle {
+    fn open(path: &Path) -> Option<Self> {
+        let mut file = File::open(path).ok()?;
+        let file_len = file.metadata().ok()?.len();
+        let mut head = vec![0u8; 4096.min(file_len as usize)];
+        file.read_exact(&mut head).ok()?;
+
+        let mut header = Reader::new(&head, true);
+        if header.c_str()? != "UnityFS" {
+            return None;
+        }
+        let version = header.u32()?;
+        header.c_str()?;
+        header.c_str()?;
+        header.u64()?;
+        let info_compressed = header.u32()?;
+        let info_size = header.u32()?;
+        let flags = header.u32()?;
+        if version >= 7 {
+            header.align(16);
+        }
+
+        if info_size > MAX_BLOCK_BYTES {
+            return None;
+        }
+        let info_at_end = flags & 0x80 != 0;
+        let info_offset = if info_at_end {
+            file_len.checked_sub(info_compressed as u64)?
+        } else {
+            header.pos as u64
+        };
+        if info_offset + info_compressed as u64 > file_len {
+            return None;
+        }
+        let mut raw_info = vec![0u8; info_compressed as usize];
+        file.seek(SeekFrom::Start(info_offset)).ok()?;
+        file.read_exact(&mut raw_info).ok()?;
+        let info = decompress(&raw_info, flags, info_size as usize)?;
+
+        let mut data_start = header.pos as u64
+            + if info_at_end {
+                0
+            } else {
+                info_compressed as u64
+            };
+        if flags & 0x200 != 0 {
+            data_start = data_start.div_ceil(16) * 16;
+        }
+
+        let mut reader = Reader::new(&info, true);
+        reader.skip(16)?;
+        let block_count = reader.u32()?;
+        let mut blocks = Vec::new();
+        let (mut file_offset, mut data_offset) = (data_start, 0u64);
+        for _ in 0..block_count {
+            let size = reader.u32()?;
+            let compressed_size = reader.u32()?;
+            let flags = reader.u16()?;
+            blocks.push(Block {
+                file_offset,
+                data_offset,
+                compressed_size,
+                size,
+                flags,
+            });
+            file_offset += compressed_size as u64;
+            data_offset += size as u64;
+        }
+        let node_count = reader.u32()?;
+        let mut nodes = Vec::new();
+        for _ in 0..node_count {
+            let offset = reader.u64()?;
+            let size = reader.u64()?;
+            reader.u32()?;
+            let path = reader.c_str()?;
+            nodes.push(Node { offset, size, path });
+        }
+        Some(Self {
+            file,
+            file_len,
+            blocks,
+            nodes,
+            cached: None,
+        })
+    }
+
+    fn block(&mut self, index: usize) -> Option<&[u8]> {
+        if self.cached.as_ref().map(|(i, _)| *i) != Some(index) {
+            self.cached = None;
+            let block = self.blocks.get(index)?;
+            if block.size > MAX_BLOCK_BYTES
+                || block.compressed_size > MAX_BLOCK_BYTES
+                || block.file_offset + block.compressed_size as u64 > self.file_len
+            {
+                return None;
+            }
+            let mut raw = vec![0u8; block.compressed_size as usize];
+            self.file.seek(SeekFrom::Start(block.file_offset)).ok()?;
+            self.file.read_exact(&mut raw).ok()?;
+            let data = decompress(&raw, block.flags as u32, block.size as usize)?;
+            self.cached = Some((index, data));
+        }
+        self.cached.as_ref().map(|(_, data)| data.as_slice())
+    }
+
+    /// Reads `len` bytes at `offset` in the bundle's uncompressed data.
+    fn read(&mut self, offset: u64, len: u64) -> Option<Vec<u8>> {
+        if len > MAX_BLOCK_BYTES as u64 {
+            return None;
+        }
+        let end = offset.checked_add(len)?;
+        let mut out = Vec::with_capacity(len as usize);
+        let overlapping: Vec<usize> = (0..self.blocks.len())
+            .filter(|&i| {
+                let b = &self.blocks[i];
+                b.data_offset < end && b.data_offset + b.size as u64 > offset
+            })
+            .collect();
+        for index in overlapping {
+            let start = self.blocks[index].data_offset;
+            let data = self.block(index)?;
+            let from = offset.saturating_sub(start) as usize;
+            let to = ((end - start) as usize).min(data.len());
+            out.extend_from_slice(data.get(from..to)?);
+        }
+        (out.len() as u64 == len).then_some(out)
+    }
+
+    fn node_bytes(&mut self, index: usize) -> Option<Vec<u8>> {
+        let (offset, size) = (self.nodes[index].offset, self.nodes[index].size);
+        self.read(offset, size)
+    }
+}
+
+/// `(major, minor)` from a Unity version such as `2019.3.15f1`.
+fn unity_version(text: &str) -> (u32, u32) {
+    let mut parts = text
+        .split(|c: char| !c.is_ascii_digit())
+        .map(|p| p.parse().unwrap_or(0));
+    (parts.next().unwrap_or(0), parts.next().unwrap_or(0))
+}
+
+/// A `Texture2D` object inside a serialized file.
+struct TextureObject<'a> {
+    name: String,
+    unity: (u32, u32),
+    data: &'a [u8],
+    big_endian: bool,
+}
+
+/// Lists the `Texture2D` objects of a serialized file along with their names.
+fn textures(file: &[u8]) -> Option<Vec<TextureObject<'_>>> {
+    let mut header = Reader::new(file, true);
+    let mut metadata_size = header.u32()? as u64;
+    header.u32()?;
+    let version = header.u32()?;
+    let mut data_offset = header.u32()? as u64;
+    let big_endian = if version >= 9 {
+        let endian = header.u8()?;
+        header.skip(3)?;
+        endian != 0
+    } else {
+        true
+    };
+    if version >= 22 {
+        metadata_size = header.u32()? as u64;
+        header.u64()?;
+        data_offset = header.u64()?;
+        header.u64()?;
+    }
+    if !(17..=22).contains(&version) || metadata_size == 0 {
+        return None;
+    }
+
+    let mut reader = Reader {
+        bytes: file,
+        pos: header.pos,
+        big_endian,
+    };
+    let unity = unity_version(&reader.c_str()?);
+    reader.i32()?;
+    let type_tree = reader.u8()? != 0;
+    let type_count = reader.u32()?;
+    let mut class_ids = Vec::new();
+    for _ in 0..type_count {
+        let class_id = reader.i32()?;
+        reader.u8()?;
+        reader.u16()?;
+        if class_id == 114 {
+            reader.skip(16)?;
+        }
+        reader.skip(16)?;
+        if type_tree {
+            let node_count = reader.u32()? as usize;
+            let strings = reader.u32()? as usize;
+            let node_size = if version >= 19 { 32 } else { 24 };
+            reader.skip(node_count.checked_mul(node_size)?.checked_add(strings)?)?;
+            if version >= 21 {
+                let dependencies = reader.u32()? as usize;
+                reader.skip(dependencies.checked_mul(4)?)?;
+            }
+        }
+        class_ids.push(class_id);
+    }
+
+    let object_count = reader.u32()?;
+    let mut textures = Vec::new();
+    for _ in 0..object_count {
+        reader.align(4);
+        reader.u64()?;
+        let start = if version >= 22 {
+            reader.u64()?
+        } else {
+            reader.u32()? as u64
+        };
+        let size = reader.u32()? as usize;
+        let type_id = reader.i32()?;
+        if class_ids.get(type_id as usize) != Some(&TEXTURE_2D) {
+            continue;
+        }
+        let at = usize::try_from(data_offset.checked_add(start)?).ok()?;
+        let Some(data) = file.get(at..at.checked_add(size)?) else {
+            continue;
+        };
+        let Some(name) = Reader::new(data, big_endian).aligned_string() else {
+            continue;
+        };
+        textures.push(TextureObject {
+            name,
+            unity,
+            data,
+            big_endian,
+        });
+    }
+    Some(textures)
+}
+
+/// Where a texture's pixels live: inline, or in a `.resS` stream of the bundle.
+enum PixelSource<'a> {
+    Inline(&'a [u8]),
+    Stream {
+        path: String,
+        offset: u64,
+        size: u64,
+    },
+}
+
+struct TextureHeader<'a> {
+    width: u32,
+    height: u32,
+    format: i32,
+    pixels: PixelSource<'a>,
+}
+
+/// Reads the fields up to the pixel data. Layouts follow Unity 2017.3 through 2021.
+fn texture_header<'a>(texture: &TextureObject<'a>) -> Option<TextureHeader<'a>> {
+    let (major, minor) = texture.unity;
+    let at_least = |ma: u32, mi: u32| major > ma || (major == ma && minor >= mi);
+    if !at_least(2017, 3) || major >= 2022 {
+        return None;
+    }
+    let mut r = Reader::new(texture.data, texture.big_endian);
+    r.aligned_string()?;
+    r.i32()?;
+    r.u8()?;
+    if at_least(2020, 2) {
+        r.u8()?;
+    }
+    r.align(4);
+    let width = r.i32()?;
+    let height = r.i32()?;
+    r.i32()?;
+    if major >= 2020 {
+        r.i32()?;
+    }
+    let format = r.i32()?;
+    r.i32()?;
+    r.u8()?;
+    if major >= 2020 {
+        r.u8()?;
+    }
+    if at_least(2019, 3) {
+        r.u8()?;
+    }
+    if at_least(2018, 2) {
+        r.u8()?;
+    }
+    r.align(4);
+    if at_least(2018, 2) {
+        r.i32()?;
+    }
+    // Image count, dimension, the six texture settings, lightmap format and color space.
+    r.skip(4 * 10)?;
+    if at_least(2020, 2) {
+        let blob = r.u32()? as usize;
+        r.skip(blob)?;
+        r.align(4);
+    }
+    let inline = r.u32()? as usize;
+    let pixels = if inline > 0 {
+        PixelSource::Inline(r.take(inline)?)
+    } else {
+        let offset = if major >= 2020 {
+            r.u64()?
+        } else {
+            r.u32()? as u64
+        };
+        let size = r.u32()? as u64;
+        PixelSource::Stream {
+            path: r.aligned_string()?,
+            offset,
+            size,
+        }
+    };
+    let dimension = 1..=MAX_TEXTURE_SIZE as i32;
+    (dimension.contains(&width) && dimension.contains(&height)).then_some(TextureHeader {
+        width: width as u32,
+        height: height as u32,
+        format,
+        pixels,
+    })
+}
+
+fn rgb565(color: u16) -> [u8; 3] {
+    let r = ((color >> 11) & 0x1F) as u8;
+    let g = ((color >> 5) & 0x3F) as u8;
+    let b = (color & 0x1F) as u8;
+    [r << 3 | r >> 2, g << 2 | g >> 4, b << 3 | b >> 2]
+}
+
+/// Decodes a DXT color block into 16 RGBA pixels.
+fn dxt_colors(block: &[u8], three_color: bool) -> [[u8; 4]; 16] {
+    let c0 = u16::from_le_bytes([block[0], block[1]]);
+    let c1 = u16::from_le_bytes([block[2], block[3]]);
+    let (a, b) = (rgb565(c0), rgb565(c1));
+    let mix = |wa: u16, wb: u16, div: u16| -> [u8; 4] {
+        let channel = |i: usize| ((a[i] as u16 * wa + b[i] as u16 * wb) / div) as u8;
+        [channel(0), channel(1), channel(2), 255]
+    };
+    let palette = if c0 > c1 || !three_color {
+        [
+            [a[0], a[1], a[2], 255],
+            [b[0], b[1], b[2], 255],
+            mix(2, 1, 3),
+            mix(1, 2, 3),
+        ]
+    } else {
+        [
+            [a[0], a[1], a[2], 255],
+            [b[0], b[1], b[2], 255],
+            mix(1, 1, 2),
+            [0, 0, 0, 0],
+        ]
+    };
+    let indices = u32::from_le_bytes([block[4], block[5], block[6], block[7]]);
+    std::array::from_fn(|i| palette[((indices >> (2 * i)) & 0b11) as usize])
+}
+
+fn dxt5_alpha(block: &[u8]) -> [u8; 16] {
+    let (a0, a1) = (block[0] as u16, block[1] as u16);
+    let palette: [u8; 8] = std::array::from_fn(|i| match i {
+        0 => a0 as u8,
+        1 => a1 as u8,
+        _ if a0 > a1 => ((a0 * (8 - i as u16) + a1 * (i as u16 - 1)) / 7) as u8,
+        6 => 0,
+        7 => 255,
+        _ => ((a0 * (6 - i as u16) + a1 * (i as u16 - 1)) / 5) as u8,
+    });
+    let mut bits = [0u8; 8];
+    bits[..6].copy_from_slice(&block[2..8]);
+    let indices = u64::from_le_bytes(bits);
+    std::array::from_fn(|i| palette[((indices >> (3 * i)) & 0b111) as usize])
+}
+
+fn decode_dxt(data: &[u8], width: u32, height: u32, dxt5: bool) -> Option<RgbaImage> {
+    let block_size = if dxt5 { 16 } else { 8 };
+    let (blocks_x, blocks_y) = (width.div_ceil(4), height.div_ceil(4));
+    let len = (blocks_x as usize)
+        .checked_mul(blocks_y as usize)?
+        .checked_mul(block_size)?;
+    let blocks = data.get(..len)?;
+    let mut image = RgbaImage::new(width, height);
+    for (i, block) in blocks.chunks_exact(block_size).enumerate() {
+        let (bx, by) = (i as u32 % blocks_x * 4, i as u32 / blocks_x * 4);
+        let (colors, alpha) = if dxt5 {
+            (dxt_colors(&block[8..], false), Some(dxt5_alpha(block)))
+        } else {
+            (dxt_colors(block, true), None)
+        };
+        for (p, mut color) in colors.into_iter().enumerate() {
+            let (x, y) = (bx + p as u32 % 4, by + p as u32 / 4);
+            if x < width && y < height {
+                if let Some(alpha) = alpha {
+                    color[3] = alpha[p];
+                }
+                image.put_pixel(x, y, image::Rgba(color));
+            }
+        }
+    }
+    Some(image)
+}
+
+/// Decodes the top mip. Unity stores rows bottom-up.
+fn decode_texture(data: &[u8], width: u32, height: u32, format: i32) -> Option<RgbaImage> {
+    if width > MAX_TEXTURE_SIZE || height > MAX_TEXTURE_SIZE {
+        return None;
+    }
+    let pixels = (width as usize).checked_mul(height as usize)?;
+    let raw = |bpp: usize, to_rgba: fn(&[u8]) -> [u8; 4]| -> Option<RgbaImage> {
+        let bytes = data.get(..pixels.checked_mul(bpp)?)?;
+        let rgba = bytes.chunks_exact(bpp).flat_map(to_rgba).collect();
+        RgbaImage::from_raw(width, height, rgba)
+    };
+    let image = match format {
+        1 => raw(1, |p| [255, 255, 255, p[0]]),
+        3 => raw(3, |p| [p[0], p[1], p[2], 255]),
+        4 => raw(4, |p| [p[0], p[1], p[2], p[3]]),
+        5 => raw(4, |p| [p[1], p[2], p[3], p[0]]),
+        14 => raw(4, |p| [p[2], p[1], p[0], p[3]]),
+        10 => decode_dxt(data, width, height, false),
+        12 => decode_dxt(data, width, height, true),
+        _ => None,
+    }?;
+    Some(image::imageops::flip_vertical(&image))
+}
+
+/// Position of the first hint in the texture's name; `None` for other textures.
+fn preview_rank(name: &str) -> Option<usize> {
+    let name = name.to_lowercase();
+    PREVIEW_HINTS.iter().position(|hint| name.contains(hint))
+}
+
+/// Finds and decodes the bundle's preview texture.
+pub fn extract_preview(path: &Path) -> Result<DynamicImage, String> {
+    let mut bundle = Bundle::open(path).ok_or("Not a readable UnityFS bundle")?;
+    let serialized: Vec<usize> = (0..bundle.nodes.len())
+        .filter(|&i| {
+            let node = &bundle.nodes[i];
+            !node.path.ends_with(".resS") && !node.path.ends_with(".resource")
+        })
+        .collect();
+
+    let mut unsupported = None;
+    for index in serialized {
+        let Some(file) = bundle.node_bytes(index) else {
+            continue;
+        };
+        let Some(textures) = textures(&file) else {
+            continue;
+        };
+        let mut candidates: Vec<(usize, TextureHeader)> = textures
+            .iter()
+            .filter_map(|t| Some((preview_rank(&t.name)?, texture_header(t)?)))
+            .collect();
+        candidates
+            .sort_by_key(|(rank, h)| (*rank, std::cmp::Reverse(h.width as u64 * h.height as u64)));
+
+        for (_, header) in candidates {
+            let streamed;
+            let data = match header.pixels {
+                PixelSource::Inline(data) => data,
+                PixelSource::Stream { path, offset, size } => {
+                    let file_name = path.rsplit('/').next().unwrap_or(&path);
+                    let Some(node) = bundle.nodes.iter().find(|n| n.path == file_name) else {
+                        continue;
+                    };
+                    let Some(start) = node.offset.checked_add(offset) else {
+                        continue;
+                    };
+                    match bundle.read(start, size) {
+                        Some(bytes) => {
+                            streamed = bytes;
+                            &streamed
+                        }
+                        None => continue,
+                    }
+                }
+            };
+            match decode_texture(data, header.width, header.height, header.format) {
+                Some(image) => return Ok(DynamicImage::ImageRgba8(image)),
+                None => unsupported = Some(header.format),
+            }
+        }
+    }
+    Err(match unsupported {
+        Some(format) => format!("Preview texture format {} is not supported", format),
+        None => "No preview texture in bundle".into(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    /// A 2019.3 serialized file holding one RGBA32 `Texture2D`.
+    fn serialized_texture(name: &str, width: u32, height: u32, pixels: &[u8]) -> Vec<u8> {
+        let mut object = Vec::new();
+        object.extend((name.len() as u32).to_le_bytes());
+        object.extend(name.as_bytes());
+        object.resize(object.len().div_ceil(4) * 4, 0);
+        object.extend(0i32.to_le_bytes());
+        object.extend([0, 0, 0, 0]);
+        for value in [width, height, width * height * 4, 4, 1] {
+            object.extend(value.to_le_bytes());
+        }
+        object.extend([0, 0, 0, 0]);
+        object.extend(0i32.to_le_bytes());
+        object.extend([0u8; 40]);
+        object.extend((pixels.len() as u32).to_le_bytes());
+        object.extend(pixels);
+
+        let mut metadata = Vec::new();
+        metadata.extend(b"2019.3.15f1\0");
+        metadata.extend(19i32.to_le_bytes());
+        metadata.push(0);
+        metadata.extend(1u32.to_le_bytes());
+        metadata.extend(TEXTURE_2D.to_le_bytes());
+        metadata.push(0);
+        metadata.extend((-1i16).to_le_bytes());
+        metadata.extend([0u8; 16]);
+        metadata.extend(1u32.to_le_bytes());
+        while (20 + metadata.len()) % 4 != 0 {
+            metadata.push(0);
+        }
+        metadata.extend(1u64.to_le_bytes());
+        metadata.extend(0u32.to_le_bytes());
+        metadata.extend((object.len() as u32).to_le_bytes());
+        metadata.extend(0i32.to_le_bytes());
+
+        let data_offset = (20 + metadata.len()).div_ceil(16) * 16;
+        let mut file = Vec::new();
+        file.extend((metadata.len() as u32).to_be_bytes());
+        file.extend(((data_offset + object.len()) as u32).to_be_bytes());
+        file.extend(21u32.to_be_bytes());
+        file.extend((data_offset as u32).to_be_bytes());
+        file.extend([0, 0, 0, 0]);
+        file.extend(metadata);
+        file.resize(data_offset, 0);
+        file.extend(object);
+        file
+    }
+
+    /// Wraps `data` in a UnityFS bundle with one LZ4 block.
+    fn bundle(node_path: &str, data: &[u8]) -> Vec<u8> {
+        let block = lz4_flex::block::compress(data);
+        let mut info = vec![0u8; 16];
+        info.extend(1u32.to_be_bytes());
+        info.extend((data.len() as u32).to_be_bytes());
+        info.extend((block.len() as u32).to_be_bytes());
+        info.extend(2u16.to_be_bytes());
+        info.extend(1u32.to_be_bytes());
+        info.extend(0u64.to_be_bytes());
+        info.extend((data.len() as u64).to_be_bytes());
+        info.extend(4u32.to_be_bytes());
+        info.extend(node_path.as_bytes());
+        info.push(0);
+
+        let mut out = Vec::new();
+        out.extend(SIGNATURE);
+        out.extend(6u32.to_be_bytes());
+        out.extend(b"5.x.x\0");
+        out.extend(b"2019.3.15f1\0");
+        out.extend(0u64.to_be_bytes());
+        out.extend((info.len() as u32).to_be_bytes());
+        out.extend((info.len() as u32).to_be_bytes());
+        out.extend(0u32.to_be_bytes());
+        out.extend(info);
+        out.extend(block);
+        out
+    }
+
+    #[test]
+    fn extracts_named_preview_texture() {
+        // Two rows, bottom row red and top row blue as Unity stores them.
+        let pixels = [
+            [255, 0, 0, 255],
+            [255, 0, 0, 255],
+            [0, 0, 255, 255],
+            [0, 0, 255, 255],
+        ]
+        .concat();
+        let file = serialized_texture("MapPreview", 2, 2, &pixels);
+        let path = std::env::temp_dir().join(format!("xlfm-bundle-{}", uuid::Uuid::new_v4()));
+        fs::write(&path, bundle("CAB-0123", &file)).unwrap();
+
+        assert!(is_asset_bundle(&path));
+        let image = extract_preview(&path).unwrap().to_rgba8();
+        assert_eq!(image.dimensions(), (2, 2));
+        assert_eq!(image.get_pixel(0, 0).0, [0, 0, 255, 255]);
+        assert_eq!(image.get_pixel(1, 1).0, [255, 0, 0, 255]);
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn rejects_out_of_range_sizes() {
+        let file = serialized_texture("Preview", 1, 1, &[1, 2, 3, 4]);
+        let path = std::env::temp_dir().join(format!("xlfm-bundle-{}", uuid::Uuid::new_v4()));
+
+        // Block table larger than the file.
+        let mut bytes = bundle("CAB-0123", &file);
+        let info_compressed = SIGNATURE.len() + 4 + 6 + 12 + 8;
+        bytes[info_compressed..info_compressed + 4].copy_from_slice(&u32::MAX.to_be_bytes());
+        fs::write(&path, &bytes).unwrap();
+        assert!(extract_preview(&path).is_err());
+
+        // Every truncation fails cleanly.
+        let bytes = bundle("CAB-0123", &file);
+        for len in 0..bytes.len() {
+            fs::write(&path, &bytes[..len]).unwrap();
+            assert!(extract_preview(&path).is_err(), "cut at {}", len);
+        }
+        fs::remove_file(path).unwrap();
+
+        assert!(decode_texture(&[], u32::MAX, u32::MAX, 4).is_none());
+        assert!(decode_texture(&[0; 8], 8, 8, 10).is_none());
+        assert!(decode_texture(&[0; 8], 4, 4, 10).is_some());
+    }
+
+    #[test]
+    fn ignores_textures_without_a_preview_name() {
+        let file = serialized_texture("Asphalt_Albedo", 1, 1, &[1, 2, 3, 4]);
+        let path = std::env::temp_dir().join(format!("xlfm-bundle-{}", uuid::Uuid::new_v4()));
+        fs::write(&path, bundle("CAB-0123", &file)).unwrap();
+
+        assert!(extract_preview(&path).is_err());
+        fs::remove_file(path).unwrap();
+    }
+}
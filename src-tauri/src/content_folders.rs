@@ -0,0 +1,518 @@
+// src-tauri/src/content_folders.rs
+
+//! Registry of the SkaterXL content folders XLFM can relocate behind a link.
+//! Each folder can be inspected, linked, relocated and restored on its own.
+
+use crate::backups::swap_in_backup;
+use crate::error::{CommandError, CommandResult};
+use crate::link_backend::{
+    link_folder_with_backup, remove_link, LinkBackend, PlatformLinkBackend,
+};
+use crate::models::{RelocateMode, RelocationResult};
+use crate::relocate::relocate_folder;
+use crate::settings;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+use tauri::{command, AppHandle, Emitter};
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[serde(rename_all = "camelCase")]
+pub enum ContentFolder {
+    Maps,
+    Mods,
+    Gear,
+    Replays,
+    Templates,
+}
+
+impl ContentFolder {
+    pub const ALL: [ContentFolder; 5] = [
+        ContentFolder::Maps,
+        ContentFolder::Mods,
+        ContentFolder::Gear,
+        ContentFolder::Replays,
+        ContentFolder::Templates,
+    ];
+
+    /// Folder name inside the SkaterXL user data directory.
+    pub fn dir_name(self) -> &'static str {
+        match self {
+            ContentFolder::Maps => "Maps",
+            ContentFolder::Mods => "Mods",
+            ContentFolder::Gear => "Gear",
+            ContentFolder::Replays => "Replays",
+            ContentFolder::Templates => "Templates",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ContentFolderStatus {
+    pub kind: ContentFolder,
+    pub path: PathBuf,
+    pub exists: bool,
+    pub is_link: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub link_target: Option<PathBuf>,
+}
+
+/// Resolves the SkaterXL user data root: an explicit root wins over the configured
+/// game path, which wins over auto-detection.
+pub fn skaterxl_root(app_handle: &AppHandle, root: Option<String>) -> CommandResult<PathBuf> {
+    root.filter(|r| !r.trim().is_empty())
+        .map(PathBuf::from)
+        .or_else(|| settings::current(app_handle).resolved_game_path())
+        .ok_or_else(|| {
+            CommandError::DirectoryResolution("Could not locate the SkaterXL folder".into())
+        })
+}
+
+pub fn folder_path(
+    app_handle: &AppHandle,
+    kind: ContentFolder,
+    root: Option<String>,
+) -> CommandResult<PathBuf> {
+    Ok(skaterxl_root(app_handle, root)?.join(kind.dir_name()))
+}
+
+pub fn inspect(backend: &impl LinkBackend, kind: ContentFolder, path: &Path) -> ContentFolderStatus {
+    let is_link = backend.is_link(path).unwrap_or(false);
+    ContentFolderStatus {
+        kind,
+        path: path.to_path_buf(),
+        exists: path.exists(),
+        is_link,
+        link_target: if is_link {
+            backend.read_link(path).ok()
+        } else {
+            None
+        },
+    }
+}
+
+#[command]
+pub fn list_content_folders(
+    app_handle: AppHandle,
+    root: Option<String>,
+) -> CommandResult<Vec<ContentFolderStatus>> {
+    let root = skaterxl_root(&app_handle, root)?;
+    Ok(ContentFolder::ALL
+        .iter()
+        .map(|kind| inspect(&PlatformLinkBackend, *kind, &root.join(kind.dir_name())))
+        .collect())
+}
+
+#[command]
+pub fn inspect_content_folder(
+    app_handle: AppHandle,
+    kind: ContentFolder,
+    root: Option<String>,
+) -> CommandResult<ContentFolderStatus> {
+    let path = folder_path(&app_handle, kind, root)?;
+    Ok(inspect(&PlatformLinkBackend, kind, &path))
+}
+
+/// Points a content folder at `new_folder`, backing up a real folder if one is in the way.
+/// Returns the backup path, if one was made.
+#[command]
+pub fn link_content_folder(
+    app_handle: AppHandle,
+    kind: ContentFolder,
+    new_folder: String,
+    root: Option<String>,
+) -> CommandResult<Option<PathBuf>> {
+    let path = folder_path(&app_handle, kind, root)?;
+    log::info!(
+        "[content_folders::link] {} -> {}",
+        path.display(),
+        new_folder
+    );
+    Ok(link_folder_with_backup(
+        &PlatformLinkBackend,
+        Path::new(&new_folder),
+        &path,
+    )?)
+}
+
+/// Moves or copies a content folder into `new_folder`, then links it there.
+/// Progress is emitted as `relocation_progress` events.
+#[command]
+pub async fn relocate_content_folder(
+    app_handle: AppHandle,
+    kind: ContentFolder,
+    new_folder: String,
+    mode: RelocateMode,
+    root: Option<String>,
+) -> CommandResult<RelocationResult> {
+    let path = folder_path(&app_handle, kind, root)?;
+    log::info!(
+        "[content_folders::relocate] {} -> {} ({:?})",
+        path.display(),
+        new_folder,
+        mode
+    );
+    tokio::task::spawn_blocking(move || {
+        let mut last: Option<(String, u64)> = None;
+        relocate_folder(
+            &PlatformLinkBackend,
+            &path,
+            Path::new(&new_folder),
+            mode,
+            &mut |p| {
+                let percent = (p.bytes_done * 100).checked_div(p.bytes_total).unwrap_or(0);
+                if last.as_ref() != Some(&(p.phase.clone(), percent)) {
+                    last = Some((p.phase.clone(), percent));
+                    if let Err(e) = app_handle.emit("relocation_progress", p) {
+                        log::warn!("[content_folders::relocate] emit failed: {}", e);
+                    }
+                }
+            },
+        )
+    })
+    .await?
+}
+
+/// Lists the `<folder>_backup` and `<folder>_backup_N` directories next to `path`.
+pub fn backup_dirs(path: &Path) -> Vec<PathBuf> {
+    let (Some(parent), Some(name)) = (path.parent(), path.file_name()) else {
+        return Vec::new();
+    };
+    let prefix = format!("{}_backup", name.to_string_lossy());
+    let Ok(entries) = fs::read_dir(parent) else {
+        return Vec::new();
+    };
+    entries
+        .filter_map(Result::ok)
+        .filter(|e| {
+            let name = e.file_name().to_string_lossy().into_owned();
+            match name.strip_prefix(&prefix) {
+                Some("") => true,
+                Some(rest) => rest
+                    .strip_prefix('_')
+                    .is_some_and(|n| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit())),
+                None => false,
+            }
+        })
+        .filter(|e| e.file_type().map(|t| t.is_dir()).unwrap_or(false))
+        .map(|e| e.path())
+        .collect()
+}
+
+/// Returns the newest backup directory, by modification time.
+pub fn newest_backup(path: &Path) -> Option<PathBuf> {
+    backup_dirs(path)
+        .into_iter()
+        .max_by_key(|p| fs::metadata(p).and_then(|m| m.modified()).ok())
+}
+
+/// Undoes a link: removes it and puts the newest backup back, or creates an empty folder.
+#[command]
+pub fn restore_content_folder(
+    app_handle: AppHandle,
+    kind: ContentFolder,
+    root: Option<String>,
+) -> CommandResult<ContentFolderStatus> {
+    let path = folder_path(&app_handle, kind, root)?;
+    restore_from_backup(&PlatformLinkBackend, kind, &path)
+}
+
+/// Swaps the link at `path` for its newest backup. Without a backup the link is replaced
+/// by an empty folder.
+pub(crate) fn restore_from_backup(
+    backend: &impl LinkBackend,
+    kind: ContentFolder,
+    path: &Path,
+) -> CommandResult<ContentFolderStatus> {
+    match newest_backup(path) {
+        Some(backup) => {
+            log::info!(
+                "[content_folders::restore] {} -> {}",
+                backup.display(),
+                path.display()
+            );
+            swap_in_backup(backend, path, &backup)?;
+        }
+        None => {
+            if !remove_link(backend, path)? && path.exists() {
+                return Err(CommandError::Input(format!(
+                    "{} is already a regular folder",
+                    path.display()
+                )));
+            }
+            fs::create_dir_all(path)?;
+        }
+    }
+    Ok(inspect(backend, kind, path))
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum LinkState {
+    /// A regular folder; nothing to check.
+    NotLinked,
+    /// Neither a link nor a folder exists.
+    Missing,
+    /// The link points at an existing folder.
+    Healthy,
+    /// The link points at a folder that is gone, e.g. an unplugged drive.
+    Dangling,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct LinkHealth {
+    pub kind: ContentFolder,
+    pub path: PathBuf,
+    pub state: LinkState,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub link_target: Option<PathBuf>,
+    pub target_exists: bool,
+    pub target_writable: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub free_space_bytes: Option<u64>,
+    /// Newest `_backup` folder a repair could restore.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub newest_backup: Option<PathBuf>,
+}
+
+/// Prefix of the hidden probe files written by `is_writable`.
+const WRITE_PROBE_PREFIX: &str = ".xlfm_write_test_";
+
+/// Whether `path` is a probe file from `is_writable`; the watcher drops their events.
+pub(crate) fn is_write_probe(path: &Path) -> bool {
+    path.file_name()
+        .is_some_and(|n| n.to_string_lossy().starts_with(WRITE_PROBE_PREFIX))
+}
+
+/// Writes and removes a probe file; the only reliable cross-platform writability check.
+fn is_writable(dir: &Path) -> bool {
+    let probe = dir.join(format!("{}{}", WRITE_PROBE_PREFIX, uuid::Uuid::new_v4()));
+    let ok = fs::write(&probe, b"").is_ok();
+    let _ = fs::remove_file(&probe);
+    ok
+}
+
+pub fn check_health(backend: &impl LinkBackend, kind: ContentFolder, path: &Path) -> LinkHealth {
+    let is_link = backend.is_link(path).unwrap_or(false);
+    let link_target = if is_link {
+        backend
+            .read_link(path)
+            .ok()
+            .map(|t| match path.parent() {
+                Some(parent) if t.is_relative() => parent.join(t),
+                _ => t,
+            })
+    } else {
+        None
+    };
+    // For real folders the "target" is the folder itself.
+    let effective = link_target.clone().unwrap_or_else(|| path.to_path_buf());
+    let target_exists = effective.is_dir();
+    let state = match (is_link, target_exists) {
+        (true, true) => LinkState::Healthy,
+        (true, false) => LinkState::Dangling,
+        (false, true) => LinkState::NotLinked,
+        (false, false) => LinkState::Missing,
+    };
+    LinkHealth {
+        kind,
+        path: path.to_path_buf(),
+        state,
+        link_target,
+        target_exists,
+        target_writable: target_exists && is_writable(&effective),
+        free_space_bytes: if target_exists {
+            fs2::available_space(&effective).ok()
+        } else {
+            None
+        },
+        newest_backup: newest_backup(path),
+    }
+}
+
+/// Reports link target, reachability, writability and free space for every content folder.
+#[command]
+pub fn check_content_folder_links(
+    app_handle: AppHandle,
+    root: Option<String>,
+) -> CommandResult<Vec<LinkHealth>> {
+    let root = skaterxl_root(&app_handle, root)?;
+    Ok(ContentFolder::ALL
+        .iter()
+        .map(|kind| check_health(&PlatformLinkBackend, *kind, &root.join(kind.dir_name())))
+        .collect())
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(tag = "action", rename_all = "camelCase")]
+pub enum RepairAction {
+    /// Point the link at a different folder, e.g. the drive's new mount point.
+    #[serde(rename_all = "camelCase")]
+    Repoint { new_target: String },
+    /// Drop the link and bring back the newest `_backup` folder.
+    RestoreBackup,
+}
+
+#[command]
+pub fn repair_content_folder_link(
+    app_handle: AppHandle,
+    kind: ContentFolder,
+    repair: RepairAction,
+    root: Option<String>,
+) -> CommandResult<LinkHealth> {
+    let path = folder_path(&app_handle, kind, root)?;
+    log::info!("[content_folders::repair] {} via {:?}", path.display(), repair);
+    repair_link(&PlatformLinkBackend, kind, &path, repair)
+}
+
+fn repair_link(
+    backend: &impl LinkBackend,
+    kind: ContentFolder,
+    path: &Path,
+    repair: RepairAction,
+) -> CommandResult<LinkHealth> {
+    match repair {
+        RepairAction::Repoint { new_target } => {
+            link_folder_with_backup(backend, Path::new(&new_target), path)?;
+        }
+        RepairAction::RestoreBackup => {
+            if newest_backup(path).is_none() {
+                return Err(CommandError::Input(format!(
+                    "No backup found next to {}",
+                    path.display()
+                )));
+            }
+            restore_from_backup(backend, kind, path)?;
+        }
+    }
+    Ok(check_health(backend, kind, path))
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+    use std::os::unix::fs::symlink;
+    use std::time::{Duration, SystemTime};
+
+    fn temp_dir() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("xlfm-folders-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn set_modified(dir: &Path, secs_ago: u64) {
+        let time = SystemTime::now() - Duration::from_secs(secs_ago);
+        fs::File::open(dir).unwrap().set_modified(time).unwrap();
+    }
+
+    #[test]
+    fn reports_link_states() {
+        let root = temp_dir();
+        let (maps, drive) = (root.join("Maps"), root.join("Drive"));
+        fs::create_dir_all(&drive).unwrap();
+        symlink(&drive, &maps).unwrap();
+
+        let health = check_health(&PlatformLinkBackend, ContentFolder::Maps, &maps);
+        assert_eq!(health.state, LinkState::Healthy);
+        assert!(health.target_writable);
+        assert_eq!(health.link_target, Some(drive.clone()));
+        // The writability probe is cleaned up.
+        assert_eq!(fs::read_dir(&drive).unwrap().count(), 0);
+
+        fs::remove_dir(&drive).unwrap();
+        let health = check_health(&PlatformLinkBackend, ContentFolder::Maps, &maps);
+        assert_eq!(health.state, LinkState::Dangling);
+        assert!(!health.target_exists && !health.target_writable);
+        assert_eq!(health.free_space_bytes, None);
+
+        let gear = root.join("Gear");
+        let health = check_health(&PlatformLinkBackend, ContentFolder::Gear, &gear);
+        assert_eq!(health.state, LinkState::Missing);
+        fs::create_dir_all(&gear).unwrap();
+        let health = check_health(&PlatformLinkBackend, ContentFolder::Gear, &gear);
+        assert_eq!(health.state, LinkState::NotLinked);
+        assert_eq!(health.link_target, None);
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn finds_the_newest_backup() {
+        let root = temp_dir();
+        let maps = root.join("Maps");
+        for (name, age) in [
+            ("Maps_backup", 300),
+            ("Maps_backup_1", 10),
+            ("Maps_backup_2", 200),
+        ] {
+            fs::create_dir_all(root.join(name)).unwrap();
+            set_modified(&root.join(name), age);
+        }
+        fs::create_dir_all(root.join("Maps_backup_old")).unwrap();
+        fs::write(root.join("Maps_backup_3"), "not a folder").unwrap();
+
+        let mut backups = backup_dirs(&maps);
+        backups.sort();
+        assert_eq!(
+            backups,
+            ["Maps_backup", "Maps_backup_1", "Maps_backup_2"].map(|n| root.join(n))
+        );
+        assert_eq!(newest_backup(&maps), Some(root.join("Maps_backup_1")));
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn repoints_a_dangling_link() {
+        let root = temp_dir();
+        let (maps, drive) = (root.join("Maps"), root.join("NewMount"));
+        fs::create_dir_all(&drive).unwrap();
+        symlink(root.join("OldMount"), &maps).unwrap();
+
+        let repair = RepairAction::Repoint {
+            new_target: drive.to_string_lossy().into_owned(),
+        };
+        let health = repair_link(&PlatformLinkBackend, ContentFolder::Maps, &maps, repair).unwrap();
+        assert_eq!(health.state, LinkState::Healthy);
+        assert_eq!(fs::read_link(&maps).unwrap(), drive);
+        assert_eq!(newest_backup(&maps), None);
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn restores_the_newest_backup() {
+        let root = temp_dir();
+        let maps = root.join("Maps");
+        fs::create_dir_all(root.join("Maps_backup").join("Old")).unwrap();
+        set_modified(&root.join("Maps_backup"), 100);
+        fs::create_dir_all(root.join("Maps_backup_1").join("Park")).unwrap();
+        symlink(root.join("Gone"), &maps).unwrap();
+
+        let repair = RepairAction::RestoreBackup;
+        let health = repair_link(&PlatformLinkBackend, ContentFolder::Maps, &maps, repair).unwrap();
+        assert_eq!(health.state, LinkState::NotLinked);
+        assert!(maps.join("Park").is_dir());
+        assert_eq!(newest_backup(&maps), Some(root.join("Maps_backup")));
+        // Only Maps and the older backup are left; the old link is gone.
+        assert_eq!(fs::read_dir(&root).unwrap().count(), 2);
+
+        let repair = RepairAction::RestoreBackup;
+        assert!(repair_link(&PlatformLinkBackend, ContentFolder::Maps, &maps, repair).is_err());
+        assert!(maps.join("Park").is_dir());
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn restore_without_backup_leaves_an_empty_folder() {
+        let root = temp_dir();
+        let maps = root.join("Maps");
+        symlink(root.join("Gone"), &maps).unwrap();
+
+        let status = restore_from_backup(&PlatformLinkBackend, ContentFolder::Maps, &maps).unwrap();
+        assert!(status.exists && !status.is_link);
+        assert!(restore_from_backup(&PlatformLinkBackend, ContentFolder::Maps, &maps).is_err());
+        fs::remove_dir_all(root).unwrap();
+    }
+}
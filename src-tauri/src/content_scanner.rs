@@ -0,0 +1,615 @@
+// src-tauri/src/content_scanner.rs
+
+//! One scanner for every content folder. Each kind has rules for what counts as an item,
+//! where its thumbnail comes from and which metadata is attached; thumbnail rendering,
+//! caching and pruning are shared through `thumbnail_cache`.
+
+use crate::bundle_preview::is_asset_bundle;
+use crate::content_folders::ContentFolder;
+use crate::error::{CommandError, CommandResult};
+use crate::map_commands::list_local_maps;
+use crate::map_metadata::read_sidecar;
+use crate::mod_commands::list_local_mods;
+use crate::models::*;
+use crate::settings;
+use crate::thumbnail_cache::{ThumbnailCache, ThumbnailLookup};
+use crate::thumbnail_render::{ThumbnailSize, OUTPUT_MIME};
+use crate::umm::read_mod_info;
+use crate::utils::*;
+use std::{
+    collections::{HashMap, HashSet},
+    ffi::OsStr,
+    fs,
+    path::{Path, PathBuf},
+};
+use tauri::{command, AppHandle};
+use walkdir::WalkDir;
+
+/// Image extensions in the order they are tried for `<name>.<ext>` thumbnails.
+const THUMBNAIL_PRIORITY: [&str; 6] = ["png", "jpg", "jpeg", "webp", "gif", "bmp"];
+/// How far below a folder item deep thumbnail search looks.
+const DEEP_SEARCH_DEPTH: usize = 3;
+/// Image names, lowercased, preferred by deep search over shallower images.
+const PREVIEW_NAME_HINTS: [&str; 5] = ["preview", "thumbnail", "thumb", "cover", "screenshot"];
+
+/// Which loose files in a content folder are items.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FileRule {
+    None,
+    /// Anything not in `EXCLUDED_FILE_EXTS`.
+    AllExceptExcluded,
+    /// Image files, which are their own thumbnail.
+    Images,
+}
+
+/// Metadata attached to each item.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum MetadataRule {
+    None,
+    /// The `.xlfm.json` sidecar written for mod.io installs.
+    Sidecar,
+    /// Unity Mod Manager's `Info.json`, for folders.
+    UmmManifest,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct ContentRules {
+    pub files: FileRule,
+    /// Use any image inside a folder item when none is named after the folder.
+    pub deep_thumbnails: bool,
+    /// Fall back to a preview texture inside the item's asset bundle.
+    pub embedded_previews: bool,
+    pub metadata: MetadataRule,
+}
+
+impl ContentRules {
+    /// Folders are items for every kind; the kinds differ in loose files and metadata.
+    pub fn for_kind(kind: ContentFolder) -> Self {
+        match kind {
+            ContentFolder::Maps => Self {
+                files: FileRule::AllExceptExcluded,
+                deep_thumbnails: true,
+                embedded_previews: true,
+                metadata: MetadataRule::Sidecar,
+            },
+            // UMM only loads folders; loose files in Mods are leftovers.
+            ContentFolder::Mods => Self {
+                files: FileRule::None,
+                deep_thumbnails: true,
+                embedded_previews: false,
+                metadata: MetadataRule::UmmManifest,
+            },
+            ContentFolder::Gear | ContentFolder::Templates => Self {
+                files: FileRule::Images,
+                deep_thumbnails: true,
+                embedded_previews: false,
+                metadata: MetadataRule::None,
+            },
+            ContentFolder::Replays => Self {
+                files: FileRule::AllExceptExcluded,
+                deep_thumbnails: false,
+                embedded_previews: false,
+                metadata: MetadataRule::None,
+            },
+        }
+    }
+}
+
+fn is_image(path: &Path) -> bool {
+    path.extension()
+        .and_then(OsStr::to_str)
+        .is_some_and(|ext| THUMBNAIL_EXTS.contains(ext.to_lowercase().as_str()))
+}
+
+fn mime_for(path: &Path) -> Option<String> {
+    path.extension()
+        .and_then(OsStr::to_str)
+        .and_then(get_mime_type_from_extension)
+}
+
+/// Returns `Ok(false)` when the folder does not exist, and an error when it is a file.
+pub fn check_listing_root(path: &Path) -> CommandResult<bool> {
+    if !path.exists() {
+        return Ok(false);
+    }
+    if !path.is_dir() {
+        return Err(CommandError::Input(format!(
+            "Path exists but is not a directory: {}",
+            path.display()
+        )));
+    }
+    Ok(true)
+}
+
+pub fn sort_by_name(entries: &mut [FsEntry]) {
+    entries.sort_by_cached_key(|e| e.name.as_deref().unwrap_or("").to_lowercase());
+}
+
+pub struct ContentScanner {
+    rules: ContentRules,
+    cache: Option<ThumbnailCache>,
+    /// Items left without a thumbnail, which may still take a mod.io logo.
+    unresolved: HashSet<PathBuf>,
+    /// Items whose render is queued; a logo becomes their fallback.
+    pending: HashSet<PathBuf>,
+}
+
+impl ContentScanner {
+    /// Without a cache, entries point at their original thumbnail.
+    pub fn new(kind: ContentFolder, cache: Option<ThumbnailCache>) -> Self {
+        Self {
+            rules: ContentRules::for_kind(kind),
+            cache,
+            unresolved: HashSet::new(),
+            pending: HashSet::new(),
+        }
+    }
+
+    pub fn for_app(app_handle: &AppHandle, kind: ContentFolder) -> Self {
+        Self::new(kind, ThumbnailCache::for_app(app_handle, kind))
+    }
+
+    /// `<folder>/<name>.<ext>`, else (for deep rules) an image up to a few levels down:
+    /// one named like a preview first, then the shallowest.
+    fn folder_thumbnail(&self, folder: &Path, name: &str) -> Option<PathBuf> {
+        THUMBNAIL_PRIORITY
+            .iter()
+            .map(|ext| folder.join(format!("{}.{}", name, ext)))
+            .find(|p| p.is_file())
+            .or_else(|| {
+                if !self.rules.deep_thumbnails {
+                    return None;
+                }
+                WalkDir::new(folder)
+                    .min_depth(1)
+                    .max_depth(DEEP_SEARCH_DEPTH)
+                    .follow_links(true)
+                    .into_iter()
+                    .filter_map(Result::ok)
+                    .filter(|e| e.file_type().is_file() && is_image(e.path()))
+                    .min_by_key(|e| {
+                        let stem = e
+                            .path()
+                            .file_stem()
+                            .map(|s| s.to_string_lossy().to_lowercase());
+                        let hinted = stem.is_some_and(|stem| {
+                            PREVIEW_NAME_HINTS.iter().any(|hint| stem.contains(hint))
+                        });
+                        (!hinted, e.depth(), e.path().to_path_buf())
+                    })
+                    .map(|e| e.into_path())
+            })
+    }
+
+    /// The asset bundle an item's preview can be extracted from: a bundle file itself, or
+    /// the largest bundle directly inside a folder.
+    fn bundle_source(path: &Path, is_directory: bool) -> Option<PathBuf> {
+        if !is_directory {
+            return is_asset_bundle(path).then(|| path.to_path_buf());
+        }
+        fs::read_dir(path)
+            .ok()?
+            .filter_map(Result::ok)
+            .filter_map(|e| Some((e.metadata().ok()?, e.path())))
+            .filter(|(metadata, p)| metadata.is_file() && is_asset_bundle(p))
+            .max_by_key(|(metadata, _)| metadata.len())
+            .map(|(_, p)| p)
+    }
+
+    /// Points an item left without a thumbnail at its mod.io logo. Items still rendering
+    /// get the logo as a fallback, announced by `thumbnail-ready` if their render fails.
+    pub fn apply_logo(&mut self, entry: &mut FsEntry, mod_id: u64, url: &str) {
+        if !self.unresolved.contains(&entry.path) && !self.pending.contains(&entry.path) {
+            return;
+        }
+        let Some(cache) = &mut self.cache else {
+            return;
+        };
+        let small = cache
+            .logo_for(&entry.path, mod_id, url, ThumbnailSize::Small)
+            .ready();
+        let large = cache
+            .logo_for(&entry.path, mod_id, url, ThumbnailSize::Large)
+            .ready();
+        if small.is_some() {
+            entry.thumbnail_mime_type = small.is_some().then(|| OUTPUT_MIME.to_string());
+            entry.thumbnail_path = small;
+        }
+        if large.is_some() {
+            entry.thumbnail_large_path = large;
+        }
+    }
+
+    /// Scans one folder. Returns whether it had no entries at all, plus its items.
+    pub fn scan_folder(&mut self, folder: &Path) -> CommandResult<(bool, Vec<FsEntry>)> {
+        let children: Vec<(PathBuf, String, fs::Metadata)> = fs::read_dir(folder)
+            .map_err(|e| CommandError::Io(format!("Failed read dir {}: {}", folder.display(), e)))?
+            .filter_map(|entry| match entry {
+                Ok(entry) => {
+                    let path = entry.path();
+                    // Follow links so content linked in from elsewhere still counts as folders.
+                    match fs::metadata(&path).or_else(|_| entry.metadata()) {
+                        Ok(metadata) => {
+                            let name = entry.file_name().to_string_lossy().into_owned();
+                            Some((path, name, metadata))
+                        }
+                        Err(e) => {
+                            log::error!(
+                                "[content_scanner::scan_folder] Metadata error for {}: {}",
+                                path.display(),
+                                e
+                            );
+                            None
+                        }
+                    }
+                }
+                Err(e) => {
+                    log::error!("[content_scanner::scan_folder] Error reading entry: {}", e);
+                    None
+                }
+            })
+            .collect();
+        let is_empty = children.is_empty();
+        if let Some(cache) = &mut self.cache {
+            cache.mark_scanned(folder);
+        }
+
+        // Loose images thumbnail the file item with the same stem.
+        let mut sibling_images: HashMap<String, PathBuf> = HashMap::new();
+        for (path, _, metadata) in &children {
+            if metadata.is_file() && is_image(path) {
+                if let Some(stem) = path.file_stem().and_then(OsStr::to_str) {
+                    sibling_images
+                        .entry(stem.to_lowercase())
+                        .or_insert_with(|| path.clone());
+                }
+            }
+        }
+
+        let mut entries = Vec::new();
+        for (path, name, metadata) in children {
+            let is_directory = metadata.is_dir();
+            let is_item = is_directory
+                || match self.rules.files {
+                    FileRule::None => false,
+                    FileRule::Images => is_image(&path),
+                    FileRule::AllExceptExcluded => {
+                        path.extension().and_then(OsStr::to_str).is_none_or(|ext| {
+                            !EXCLUDED_FILE_EXTS.contains(ext.to_lowercase().as_str())
+                        })
+                    }
+                };
+            if !is_item {
+                continue;
+            }
+
+            let thumbnail = if is_directory {
+                self.folder_thumbnail(&path, &name)
+            } else if self.rules.files == FileRule::Images {
+                Some(path.clone())
+            } else {
+                path.file_stem()
+                    .and_then(OsStr::to_str)
+                    .and_then(|stem| sibling_images.get(&stem.to_lowercase()))
+                    .cloned()
+            };
+            let mut thumbnail = thumbnail.filter(|t| mime_for(t).is_some());
+            // Bundles only make sense as a source for the cache, which renders them.
+            if thumbnail.is_none() && self.rules.embedded_previews && self.cache.is_some() {
+                thumbnail = Self::bundle_source(&path, is_directory);
+            }
+            // A queued render leaves the cached paths empty until `thumbnail-ready`; without
+            // a cache the original image is used for both sizes.
+            let (thumbnail_path, thumbnail_large_path, thumbnail_mime_type) =
+                match (&thumbnail, &mut self.cache) {
+                    (Some(source), Some(cache)) => {
+                        let small = cache.thumbnail_for(&path, source, ThumbnailSize::Small);
+                        match small {
+                            ThumbnailLookup::Failed => self.unresolved.insert(path.clone()),
+                            ThumbnailLookup::Pending => self.pending.insert(path.clone()),
+                            ThumbnailLookup::Ready(_) => false,
+                        };
+                        let large = cache.thumbnail_for(&path, source, ThumbnailSize::Large);
+                        let small = small.ready();
+                        let mime = small.is_some().then(|| OUTPUT_MIME.to_string());
+                        (small, large.ready(), mime)
+                    }
+                    (Some(source), None) => {
+                        (Some(source.clone()), Some(source.clone()), mime_for(source))
+                    }
+                    (None, _) => {
+                        self.unresolved.insert(path.clone());
+                        (None, None, None)
+                    }
+                };
+
+            let (mut metadata_field, mut mod_info, mut manifest_status) = (None, None, None);
+            match self.rules.metadata {
+                MetadataRule::None => {}
+                MetadataRule::Sidecar => metadata_field = read_sidecar(&path),
+                MetadataRule::UmmManifest if is_directory => {
+                    let (info, status) = read_mod_info(&path);
+                    mod_info = info;
+                    manifest_status = Some(status);
+                }
+                MetadataRule::UmmManifest => {}
+            }
+
+            entries.push(FsEntry {
+                name: Some(name),
+                size: Some(if is_directory {
+                    calculate_directory_size(&path)
+                } else {
+                    metadata.len()
+                }),
+                modified: system_time_to_millis(metadata.modified().ok())
+                    .or_else(|| system_time_to_millis(metadata.created().ok())),
+                path,
+                is_directory,
+                thumbnail_path,
+                thumbnail_mime_type,
+                thumbnail_large_path,
+                modio_match: None,
+                metadata: metadata_field,
+                enabled: None,
+                library_root: None,
+                mod_info,
+                manifest_status,
+            });
+        }
+        sort_by_name(&mut entries);
+        Ok((is_empty, entries))
+    }
+
+    /// Prunes thumbnails of scanned folders that no item used and saves the cache.
+    pub fn finish(self) -> usize {
+        match self.cache.map(ThumbnailCache::finish) {
+            Some(Ok(removed)) => removed,
+            Some(Err(e)) => {
+                log::warn!(
+                    "[content_scanner::finish] Failed to save thumbnail cache: {}",
+                    e
+                );
+                0
+            }
+            None => 0,
+        }
+    }
+}
+
+/// Lists any content folder. Maps and Mods get their extra listing features (disabled
+/// stores, libraries, catalog matching, UMM state); other kinds are a plain scan.
+#[command]
+pub fn list_local_content(
+    app_handle: AppHandle,
+    kind: ContentFolder,
+    folder: Option<String>,
+) -> CommandResult<DirectoryListingResult> {
+    match kind {
+        ContentFolder::Maps => return list_local_maps(app_handle, folder),
+        ContentFolder::Mods => return list_local_mods(app_handle, folder),
+        _ => {}
+    }
+    let path = settings::resolve_content_root(&app_handle, folder, kind)?;
+    if !check_listing_root(&path)? {
+        return Ok(DirectoryListingResult {
+            status: ListingStatus::DoesNotExist,
+            entries: Vec::new(),
+            path,
+        });
+    }
+    let mut scanner = ContentScanner::for_app(&app_handle, kind);
+    let (is_empty, entries) = scanner.scan_folder(&path)?;
+    scanner.finish();
+    log::info!(
+        "[content_scanner::list_local_content] {:?}: {} entries",
+        kind,
+        entries.len()
+    );
+    Ok(DirectoryListingResult {
+        status: if is_empty {
+            ListingStatus::ExistsAndEmpty
+        } else {
+            ListingStatus::ExistsAndPopulated
+        },
+        entries,
+        path,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("xlfm-scan-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn touch(path: &Path, contents: &str) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+    }
+
+    fn names(entries: &[FsEntry]) -> Vec<&str> {
+        entries.iter().filter_map(|e| e.name.as_deref()).collect()
+    }
+
+    #[test]
+    fn maps_include_folders_and_map_files_with_thumbnails() {
+        let root = temp_dir();
+        let maps = root.join("Maps");
+        touch(&maps.join("Park").join("Park.jpg"), "img");
+        touch(&maps.join("Park").join("park_bundle"), "map");
+        touch(&maps.join("Plaza"), "map");
+        touch(&maps.join("plaza.png"), "img");
+        touch(&maps.join("readme.txt"), "text");
+
+        let (is_empty, entries) = ContentScanner::new(ContentFolder::Maps, None)
+            .scan_folder(&maps)
+            .unwrap();
+
+        assert!(!is_empty);
+        assert_eq!(names(&entries), ["Park", "Plaza"]);
+        assert_eq!(
+            entries[0].thumbnail_path,
+            Some(maps.join("Park").join("Park.jpg"))
+        );
+        assert_eq!(
+            entries[0].thumbnail_mime_type.as_deref(),
+            Some("image/jpeg")
+        );
+        assert_eq!(entries[1].thumbnail_path, Some(maps.join("plaza.png")));
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn mods_are_folders_with_manifests() {
+        let root = temp_dir();
+        let mods = root.join("Mods");
+        touch(
+            &mods.join("XLGearModifier").join("Info.json"),
+            r#"{ "Id": "XLGearModifier", "Version": "1.0.0" }"#,
+        );
+        touch(&mods.join("XLGearModifier").join("preview.png"), "img");
+        touch(&mods.join("Broken").join("Info.json"), "{ not json");
+        touch(&mods.join("stray.zip"), "zip");
+        touch(&mods.join("notes.md"), "text");
+
+        let (_, entries) = ContentScanner::new(ContentFolder::Mods, None)
+            .scan_folder(&mods)
+            .unwrap();
+
+        assert_eq!(names(&entries), ["Broken", "XLGearModifier"]);
+        assert!(matches!(
+            entries[0].manifest_status,
+            Some(ManifestStatus::Malformed { .. })
+        ));
+        assert_eq!(
+            entries[1].mod_info.as_ref().map(|i| i.id.as_str()),
+            Some("XLGearModifier")
+        );
+        assert_eq!(
+            entries[1].thumbnail_path,
+            Some(mods.join("XLGearModifier").join("preview.png"))
+        );
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn gear_images_are_their_own_thumbnail() {
+        let root = temp_dir();
+        let gear = root.join("Gear");
+        touch(&gear.join("Shirt.png"), "img");
+        touch(&gear.join("Shirt.json"), "{}");
+
+        let (_, entries) = ContentScanner::new(ContentFolder::Gear, None)
+            .scan_folder(&gear)
+            .unwrap();
+
+        assert_eq!(names(&entries), ["Shirt.png"]);
+        assert_eq!(entries[0].thumbnail_path, Some(gear.join("Shirt.png")));
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn deep_search_prefers_preview_names_over_depth() {
+        let root = temp_dir();
+        let maps = root.join("Maps");
+        touch(&maps.join("Park").join("Park"), "bundle");
+        touch(
+            &maps.join("Park").join("Textures").join("asphalt.png"),
+            "img",
+        );
+        touch(
+            &maps
+                .join("Park")
+                .join("Assets")
+                .join("UI")
+                .join("Preview.jpg"),
+            "img",
+        );
+
+        let (_, entries) = ContentScanner::new(ContentFolder::Maps, None)
+            .scan_folder(&maps)
+            .unwrap();
+
+        assert_eq!(
+            entries[0].thumbnail_path,
+            Some(
+                maps.join("Park")
+                    .join("Assets")
+                    .join("UI")
+                    .join("Preview.jpg")
+            )
+        );
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn empty_folder_reports_empty() {
+        let root = temp_dir();
+        let (is_empty, entries) = ContentScanner::new(ContentFolder::Replays, None)
+            .scan_folder(&root)
+            .unwrap();
+        assert!(is_empty);
+        assert!(entries.is_empty());
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn caches_thumbnails_and_prunes_orphans() {
+        let root = temp_dir();
+        let maps = root.join("Maps");
+        let cache = root.join("cache");
+        fs::create_dir_all(maps.join("Park")).unwrap();
+        image::RgbImage::new(64, 36)
+            .save(maps.join("Park").join("Park.png"))
+            .unwrap();
+        touch(&cache.join("maps").join("orphan.png"), "old");
+
+        let thumbnails = ThumbnailCache::open(cache.clone(), ContentFolder::Maps, u64::MAX);
+        let mut scanner = ContentScanner::new(ContentFolder::Maps, Some(thumbnails));
+        let (_, entries) = scanner.scan_folder(&maps).unwrap();
+        let cached = entries[0].thumbnail_path.clone().unwrap();
+        assert!(cached.starts_with(&cache));
+        assert_eq!(
+            entries[0].thumbnail_mime_type.as_deref(),
+            Some("image/jpeg")
+        );
+        assert_eq!(image::image_dimensions(&cached).unwrap(), (320, 180));
+
+        assert_eq!(scanner.finish(), 1);
+        assert!(cached.is_file());
+        assert!(!cache.join("maps").join("orphan.png").exists());
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn pruning_keeps_thumbnails_of_other_folders() {
+        let root = temp_dir();
+        let (maps, custom) = (root.join("Maps"), root.join("Custom"));
+        let cache = root.join("cache");
+        for folder in [&maps, &custom] {
+            fs::create_dir_all(folder.join("Park")).unwrap();
+            image::RgbImage::new(64, 36)
+                .save(folder.join("Park").join("Park.png"))
+                .unwrap();
+        }
+        let scan = |folder: &Path| {
+            let thumbnails = ThumbnailCache::open(cache.clone(), ContentFolder::Maps, u64::MAX);
+            let mut scanner = ContentScanner::new(ContentFolder::Maps, Some(thumbnails));
+            let (_, entries) = scanner.scan_folder(folder).unwrap();
+            (entries[0].thumbnail_path.clone().unwrap(), scanner.finish())
+        };
+
+        let (main, _) = scan(&maps);
+        let (_, removed) = scan(&custom);
+        assert_eq!(removed, 0);
+        assert!(main.is_file());
+        // The main folder's entry survived too, so its next listing reuses the render.
+        assert_eq!(scan(&maps), (main, 0));
+        fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -0,0 +1,243 @@
+// src-tauri/src/dll_conflicts.rs
+
+//! Finds library DLLs (Harmony, Newtonsoft.Json, …) that are installed more than once in
+//! different versions across the Mods folder and the game's Managed folder. Mono loads
+//! whichever copy comes first, so a mod built against another version can fail to load.
+
+use crate::assembly_info::read_assembly_identity;
+use crate::content_folders::ContentFolder;
+use crate::error::CommandResult;
+use crate::mod_compat::compare_versions;
+use crate::settings;
+use crate::umm::game_install_dir;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+use tauri::{command, AppHandle};
+use walkdir::WalkDir;
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum DllSource {
+    Mods,
+    /// The game's `SkaterXL_Data/Managed`, including the loader's own folder.
+    Managed,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct AssemblyCopy {
+    pub path: PathBuf,
+    pub version: String,
+    pub source: DllSource,
+    /// The mod folder the copy ships with, for copies in Mods.
+    pub mod_folder: Option<PathBuf>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct AssemblyConflict {
+    /// Assembly name as written in the first copy found.
+    pub name: String,
+    /// Distinct versions, oldest first.
+    pub versions: Vec<String>,
+    pub copies: Vec<AssemblyCopy>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct DllConflictReport {
+    pub mods_path: PathBuf,
+    pub managed_path: Option<PathBuf>,
+    /// .NET assemblies read; native DLLs are skipped.
+    pub assemblies_scanned: usize,
+    pub conflicts: Vec<AssemblyConflict>,
+}
+
+fn scan_dlls(root: &Path, source: DllSource) -> Vec<(String, AssemblyCopy)> {
+    WalkDir::new(root)
+        .follow_links(true)
+        .into_iter()
+        .filter_map(Result::ok)
+        .filter(|e| e.file_type().is_file())
+        .filter(|e| {
+            e.path()
+                .extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("dll"))
+        })
+        .filter_map(|e| {
+            let identity = read_assembly_identity(e.path())?;
+            let mod_folder = (source == DllSource::Mods)
+                .then(|| e.path().strip_prefix(root).ok()?.components().next())
+                .flatten()
+                .map(|first| root.join(first))
+                .filter(|folder| folder != e.path());
+            Some((
+                identity.name,
+                AssemblyCopy {
+                    path: e.path().to_path_buf(),
+                    version: identity.version,
+                    source,
+                    mod_folder,
+                },
+            ))
+        })
+        .collect()
+}
+
+/// Groups copies by assembly name (case-insensitive) and keeps the groups that disagree
+/// on version.
+pub fn find_conflicts(copies: Vec<(String, AssemblyCopy)>) -> Vec<AssemblyConflict> {
+    let mut groups: BTreeMap<String, AssemblyConflict> = BTreeMap::new();
+    for (name, copy) in copies {
+        let group = groups
+            .entry(name.to_lowercase())
+            .or_insert_with(|| AssemblyConflict {
+                name,
+                versions: Vec::new(),
+                copies: Vec::new(),
+            });
+        if !group.versions.contains(&copy.version) {
+            group.versions.push(copy.version.clone());
+        }
+        group.copies.push(copy);
+    }
+    groups
+        .into_values()
+        .filter(|g| g.versions.len() > 1)
+        .map(|mut g| {
+            g.versions.sort_by(|a, b| compare_versions(a, b));
+            g.copies.sort_by(|a, b| a.path.cmp(&b.path));
+            g
+        })
+        .collect()
+}
+
+/// Scans Mods and the game's Managed folder for assemblies installed in several versions.
+#[command]
+pub fn scan_dll_conflicts(
+    app_handle: AppHandle,
+    folder: Option<String>,
+) -> CommandResult<DllConflictReport> {
+    let mods_path = settings::resolve_content_root(&app_handle, folder, ContentFolder::Mods)?;
+    let managed_path = game_install_dir(&app_handle)
+        .map(|dir| dir.join("SkaterXL_Data").join("Managed"))
+        .filter(|p| p.is_dir());
+
+    let mut copies = scan_dlls(&mods_path, DllSource::Mods);
+    if let Some(managed) = &managed_path {
+        copies.extend(scan_dlls(managed, DllSource::Managed));
+    } else {
+        log::warn!("[dll_conflicts::scan] Game Managed folder not found, scanning Mods only");
+    }
+    let assemblies_scanned = copies.len();
+    let conflicts = find_conflicts(copies);
+    log::info!(
+        "[dll_conflicts::scan] {} assemblies, {} conflicts",
+        assemblies_scanned,
+        conflicts.len()
+    );
+    Ok(DllConflictReport {
+        mods_path,
+        managed_path,
+        assemblies_scanned,
+        conflicts,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    /// A handcrafted assembly named `XLFixture`, version 1.2.3.4.
+    const MANAGED: &[u8] = include_bytes!("../tests/fixtures/managed.dll");
+    const NATIVE: &[u8] = include_bytes!("../tests/fixtures/native.dll");
+
+    fn copy(name: &str, path: &str, version: &str) -> (String, AssemblyCopy) {
+        (
+            name.into(),
+            AssemblyCopy {
+                path: PathBuf::from(path),
+                version: version.into(),
+                source: DllSource::Mods,
+                mod_folder: None,
+            },
+        )
+    }
+
+    #[test]
+    fn groups_names_case_insensitively() {
+        let conflicts = find_conflicts(vec![
+            copy("0Harmony", "/Mods/B/0Harmony.dll", "2.0.4.0"),
+            copy("0harmony", "/Mods/A/0harmony.dll", "2.2.2.0"),
+            copy("0HARMONY", "/Mods/C/0HARMONY.dll", "2.0.4.0"),
+        ]);
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].name, "0Harmony");
+        assert_eq!(conflicts[0].versions, ["2.0.4.0", "2.2.2.0"]);
+        let paths: Vec<_> = conflicts[0].copies.iter().map(|c| c.path.clone()).collect();
+        assert_eq!(
+            paths,
+            [
+                "/Mods/A/0harmony.dll",
+                "/Mods/B/0Harmony.dll",
+                "/Mods/C/0HARMONY.dll"
+            ]
+            .map(PathBuf::from)
+        );
+    }
+
+    #[test]
+    fn same_version_copies_are_not_conflicts() {
+        let conflicts = find_conflicts(vec![
+            copy("Newtonsoft.Json", "/Mods/A/Newtonsoft.Json.dll", "12.0.0.0"),
+            copy("Newtonsoft.Json", "/Mods/B/Newtonsoft.Json.dll", "12.0.0.0"),
+            copy("Solo", "/Mods/C/Solo.dll", "1.0.0.0"),
+        ]);
+        assert!(conflicts.is_empty());
+    }
+
+    #[test]
+    fn sorts_versions_numerically() {
+        let conflicts = find_conflicts(vec![
+            copy("Lib", "/Mods/A/Lib.dll", "1.10.0.0"),
+            copy("Lib", "/Mods/B/Lib.dll", "1.9.0.0"),
+            copy("Lib", "/Mods/C/Lib.dll", "1.2.0.0"),
+        ]);
+        assert_eq!(conflicts[0].versions, ["1.2.0.0", "1.9.0.0", "1.10.0.0"]);
+    }
+
+    #[test]
+    fn scans_assemblies_and_their_mod_folders() {
+        let root = std::env::temp_dir().join(format!("xlfm-dlls-{}", uuid::Uuid::new_v4()));
+        let lib = root.join("Mods").join("XLGraphics").join("lib");
+        fs::create_dir_all(&lib).unwrap();
+        fs::write(lib.join("XLFixture.dll"), MANAGED).unwrap();
+        fs::write(lib.join("native.DLL"), NATIVE).unwrap();
+        fs::write(root.join("Mods").join("Loose.dll"), MANAGED).unwrap();
+        fs::write(root.join("Mods").join("readme.txt"), "not a dll").unwrap();
+
+        let mut copies = scan_dlls(&root.join("Mods"), DllSource::Mods);
+        copies.sort_by(|a, b| a.1.path.cmp(&b.1.path));
+        assert_eq!(copies.len(), 2);
+        assert!(copies
+            .iter()
+            .all(|(name, c)| name == "XLFixture" && c.version == "1.2.3.4"));
+        // A DLL directly in Mods belongs to no mod folder.
+        assert_eq!(copies[0].1.path, root.join("Mods").join("Loose.dll"));
+        assert_eq!(copies[0].1.mod_folder, None);
+        assert_eq!(
+            copies[1].1.mod_folder,
+            Some(root.join("Mods").join("XLGraphics"))
+        );
+
+        let managed = scan_dlls(&root.join("Mods").join("XLGraphics"), DllSource::Managed);
+        assert_eq!(managed.len(), 1);
+        assert_eq!(managed[0].1.source, DllSource::Managed);
+        assert_eq!(managed[0].1.mod_folder, None);
+        fs::remove_dir_all(root).unwrap();
+    }
+}
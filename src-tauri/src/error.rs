@@ -26,6 +26,9 @@ pub enum CommandError {
 
     #[error("Trash Error: {0}")]
     TrashError(String),
+
+    #[error("Link Error: {0}")]
+    Symlink(String),
 }
 
 pub type CommandResult<T> = Result<T, CommandError>;
@@ -59,3 +62,9 @@ impl From<trash::Error> for CommandError {
         CommandError::TrashError(e.to_string())
     }
 }
+
+impl From<crate::link_backend::LinkError> for CommandError {
+    fn from(e: crate::link_backend::LinkError) -> Self {
+        CommandError::Symlink(e.to_string())
+    }
+}
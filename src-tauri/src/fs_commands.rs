@@ -2,7 +2,8 @@
 
 use crate::error::{CommandError, CommandResult};
 use crate::models::{DirectoryListingResult, FsEntry, InstallationResult, ListingStatus};
-use crate::utils::{hash_path, system_time_to_millis, THUMBNAIL_EXTS};
+use crate::thumbnail_cache::forget_item;
+use crate::utils::system_time_to_millis;
 
 use log::{debug, error, info};
 use std::{
@@ -11,7 +12,7 @@ use std::{
     io::copy,
     path::PathBuf,
 };
-use tauri::{command, AppHandle, Manager};
+use tauri::{command, AppHandle};
 use uuid::Uuid;
 use zip::ZipArchive;
 
@@ -208,6 +209,13 @@ pub fn list_directory_entries(absolute_path: String) -> CommandResult<DirectoryL
                     modified,
                     thumbnail_path: None,
                     thumbnail_mime_type: None,
+                    thumbnail_large_path: None,
+                    modio_match: None,
+                    metadata: None,
+                    enabled: None,
+                    library_root: None,
+                    mod_info: None,
+                    manifest_status: None,
                 });
             }
         }
@@ -280,15 +288,6 @@ pub fn delete_fs_entry_rust(app_handle: AppHandle, absolute_path: String) -> Com
         CommandError::TrashError(e.to_string())
     })?;
 
-    if let Ok(cache_dir) = app_handle.path().app_cache_dir() {
-        let thumb_dir = cache_dir.join("thumbnails");
-        if thumb_dir.is_dir() {
-            let key = hash_path(&path);
-            for ext in THUMBNAIL_EXTS.iter() {
-                let f = thumb_dir.join(format!("{}.{}", key, ext));
-                let _ = fs::remove_file(f);
-            }
-        }
-    }
+    forget_item(&app_handle, &path);
     Ok(())
 }
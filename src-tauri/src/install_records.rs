@@ -0,0 +1,111 @@
+// src-tauri/src/install_records.rs
+
+//! Persists which mod.io mod produced each installed path, so local content can be linked back to the catalog.
+
+use crate::utils::system_time_to_millis;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+use tauri::{AppHandle, Manager};
+
+const INSTALL_RECORDS_FILE: &str = "install_records.json";
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct InstallRecord {
+    pub mod_id: u64,
+    #[serde(default)]
+    pub modfile_id: Option<u64>,
+    #[serde(default)]
+    pub filename: Option<String>,
+    pub installed_path: PathBuf,
+    #[serde(default)]
+    pub installed_at: Option<u64>,
+}
+
+fn records_file(app_handle: &AppHandle) -> Option<PathBuf> {
+    app_handle
+        .path()
+        .app_data_dir()
+        .ok()
+        .map(|dir| dir.join(INSTALL_RECORDS_FILE))
+}
+
+/// Reads all install records. A missing or corrupt file yields an empty list.
+pub fn load_install_records(app_handle: &AppHandle) -> Vec<InstallRecord> {
+    let Some(path) = records_file(app_handle) else {
+        return Vec::new();
+    };
+    match fs::read(&path) {
+        Ok(bytes) => serde_json::from_slice(&bytes).unwrap_or_else(|e| {
+            log::warn!(
+                "[install_records::load] Ignoring corrupt records file {}: {}",
+                path.display(),
+                e
+            );
+            Vec::new()
+        }),
+        Err(_) => Vec::new(),
+    }
+}
+
+/// Stores a record for a fresh install, replacing any older record for the same path.
+pub fn record_install(
+    app_handle: &AppHandle,
+    mod_id: u64,
+    modfile_id: Option<u64>,
+    filename: Option<String>,
+    installed_path: PathBuf,
+) {
+    let Some(path) = records_file(app_handle) else {
+        log::warn!("[install_records::record] Could not resolve app data directory");
+        return;
+    };
+    let mut records = load_install_records(app_handle);
+    records.retain(|r| r.installed_path != installed_path);
+    records.push(InstallRecord {
+        mod_id,
+        modfile_id,
+        filename,
+        installed_path,
+        installed_at: system_time_to_millis(Some(SystemTime::now())),
+    });
+    save_install_records(&path, &records);
+}
+
+/// Re-points records after XLFM moves installed content, e.g. when disabling a map.
+pub fn update_installed_path(app_handle: &AppHandle, from: &Path, to: &Path) {
+    let Some(path) = records_file(app_handle) else {
+        return;
+    };
+    let mut records = load_install_records(app_handle);
+    let mut changed = false;
+    for record in records.iter_mut().filter(|r| r.installed_path == from) {
+        record.installed_path = to.to_path_buf();
+        changed = true;
+    }
+    if changed {
+        save_install_records(&path, &records);
+    }
+}
+
+fn save_install_records(path: &Path, records: &[InstallRecord]) {
+    let result = path
+        .parent()
+        .map_or(Ok(()), fs::create_dir_all)
+        .and_then(|_| {
+            serde_json::to_vec_pretty(records)
+                .map_err(std::io::Error::other)
+                .and_then(|json| fs::write(path, json))
+        });
+    if let Err(e) = result {
+        log::warn!(
+            "[install_records::save] Failed to write {}: {}",
+            path.display(),
+            e
+        );
+    }
+}
@@ -1,15 +1,21 @@
 // src-tauri/src/installer_commands.rs
 
+use crate::content_folders::ContentFolder;
 use crate::error::{CommandError, CommandResult};
 use crate::fs_commands;
+use crate::install_records::record_install;
+use crate::map_metadata::{metadata_from_modio, read_sidecar, write_sidecar};
 use crate::models::{InstallationProgress, InstallationResult};
-use crate::utils::resolve_document_path;
+use crate::settings;
+use crate::state::ModioCatalogState;
+use crate::utils::system_time_to_millis;
 
 use log::{error, info, warn};
 use reqwest;
 use std::{
     fs::{self, File},
     io::{Read, Write},
+    time::SystemTime,
 };
 use tauri::{command, AppHandle, Emitter, Manager};
 use tokio::task;
@@ -34,6 +40,7 @@ pub async fn download_and_install(
     app_handle: AppHandle,
     url: String,
     destination_subfolder: String,
+    mod_id: Option<u64>,
 ) -> CommandResult<InstallationResult> {
     let source_url = url.clone();
     info!(
@@ -42,8 +49,11 @@ pub async fn download_and_install(
     );
     let start = std::time::Instant::now();
 
-    let dest =
-        resolve_document_path(&destination_subfolder).map_err(CommandError::DirectoryResolution)?;
+    let dest = settings::resolve_content_root(
+        &app_handle,
+        Some(destination_subfolder),
+        ContentFolder::Maps,
+    )?;
     if !dest.exists() {
         fs::create_dir_all(&dest).map_err(|e| CommandError::Io(e.to_string()))?;
     } else if !dest.is_dir() {
@@ -213,5 +223,50 @@ pub async fn download_and_install(
         }
     };
 
+    let settings = settings::current(&app_handle);
+    // Sidecars describe maps, so only installs into the Maps folder get one.
+    let into_maps = settings
+        .resolved_maps_path()
+        .and_then(|maps| fs::canonicalize(maps).ok())
+        .is_some_and(|maps| fs::canonicalize(&dest).is_ok_and(|d| d == maps));
+    let policy = settings.install;
+    if let (Some(mod_id), Ok(InstallationResult { final_path: Some(installed), .. })) =
+        (mod_id, &result)
+    {
+        let catalog_mod = app_handle
+            .state::<ModioCatalogState>()
+            .mods
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|m| m.id == mod_id)
+            .cloned();
+        let modfile = catalog_mod.as_ref().and_then(|m| m.modfile.clone());
+        let filename = modfile
+            .as_ref()
+            .and_then(|f| f.filename.clone())
+            .or_else(|| url.rsplit('/').next().map(String::from));
+        if policy.record_installs {
+            record_install(
+                &app_handle,
+                mod_id,
+                modfile.map(|f| f.id),
+                filename,
+                installed.clone(),
+            );
+        }
+
+        if let Some(m) = catalog_mod.filter(|_| policy.write_sidecars && into_maps) {
+            let metadata = metadata_from_modio(
+                &m,
+                system_time_to_millis(Some(SystemTime::now())),
+                read_sidecar(installed),
+            );
+            if let Err(e) = write_sidecar(installed, &metadata) {
+                warn!("[installer] failed to write metadata sidecar: {:?}", e);
+            }
+        }
+    }
+
     result
 }
@@ -1,13 +1,33 @@
+mod assembly_info;
+mod backups;
+mod bundle_preview;
+mod content_folders;
+mod content_scanner;
+mod dll_conflicts;
 mod error;
 mod fs_commands;
 mod installer_commands;
+mod link_backend;
 mod map_commands;
 mod models;
 mod state;
+mod thumbnail_cache;
+mod thumbnail_render;
 mod utils;
 mod watcher;
 mod path_finder;
 mod mod_commands;
+mod mod_compat;
+mod mod_loaders;
+mod modio_catalog;
+mod install_records;
+mod map_matcher;
+mod map_libraries;
+mod map_metadata;
+mod map_profiles;
+mod relocate;
+mod settings;
+mod umm;
 
 use std::{collections::HashSet, sync::Mutex};
 use tokio::sync::mpsc::channel;
@@ -21,7 +41,8 @@ use tauri::{
     Listener,
 };
 use tauri_plugin_single_instance::init as single_instance_init;
-use state::{WatcherCommand, WatcherState};
+use state::{ModioCatalogState, SettingsState, WatcherCommand, WatcherState};
+use thumbnail_render::ThumbnailPool;
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
@@ -54,6 +75,10 @@ pub fn run() {
         })
         .setup(move |app| {
             watcher::run_watcher(app.handle().clone(), rx);
+            settings::load_settings(app.handle());
+            let workers = settings::current(app.handle()).limits.thumbnail_workers;
+            app.manage(ThumbnailPool::start(app.handle().clone(), workers));
+            modio_catalog::load_cached_catalog(app.handle());
 
             let tray_menu = MenuBuilder::new(app.handle())
                 .text(MenuId::new("show"), "Show XLFM")
@@ -92,6 +117,8 @@ pub fn run() {
         .plugin(tauri_plugin_http::init())
         .plugin(tauri_plugin_deep_link::init())
         .manage(watcher_state)
+        .manage(ModioCatalogState::default())
+        .manage(SettingsState::default())
         .invoke_handler(tauri::generate_handler![
             fs_commands::handle_dropped_zip,
             fs_commands::save_file,
@@ -103,13 +130,55 @@ pub fn run() {
             map_commands::is_symlink,
             map_commands::create_maps_symlink,
             map_commands::remove_maps_symlink,
+            content_folders::list_content_folders,
+            content_folders::inspect_content_folder,
+            content_folders::link_content_folder,
+            content_folders::relocate_content_folder,
+            content_folders::restore_content_folder,
+            content_folders::check_content_folder_links,
+            content_folders::repair_content_folder_link,
+            backups::list_content_backups,
+            backups::restore_content_backup,
+            backups::merge_content_backup,
+            backups::trash_content_backup,
+            map_libraries::list_map_library_roots,
+            map_libraries::add_map_library_root,
+            map_libraries::remove_map_library_root,
+            map_libraries::materialize_library_maps,
             map_commands::list_local_maps,
+            content_scanner::list_local_content,
+            thumbnail_cache::get_thumbnail_cache_usage,
+            thumbnail_cache::clear_thumbnail_cache,
+            map_profiles::list_map_profiles,
+            map_profiles::set_map_library,
+            map_profiles::save_map_profile,
+            map_profiles::delete_map_profile,
+            map_profiles::activate_map_profile,
+            map_profiles::deactivate_map_profile,
+            map_commands::enable_map,
+            map_commands::disable_map,
+            map_metadata::get_map_metadata,
+            map_metadata::set_map_metadata,
+            map_metadata::set_map_rating,
             mod_commands::list_local_mods,
+            mod_commands::enable_mod,
+            mod_commands::disable_mod,
+            mod_compat::check_mod_compatibility,
+            mod_loaders::detect_mod_loaders,
+            mod_loaders::install_mod_loader,
+            mod_loaders::uninstall_mod_loader,
+            dll_conflicts::scan_dll_conflicts,
             installer_commands::download_and_install,
+            modio_catalog::sync_modio_catalog,
             watcher::add_watched_path,
             watcher::remove_watched_path,
             watcher::update_maps_watched_path,
-            path_finder::find_skaterxl_user_data_path
+            path_finder::find_skaterxl_user_data_path,
+            path_finder::find_game_installation,
+            path_finder::find_skaterxl_user_data_candidates,
+            path_finder::discover_skaterxl_paths,
+            settings::get_settings,
+            settings::update_settings
         ])
         .build(tauri::generate_context!())
         .expect("error while building tauri application");
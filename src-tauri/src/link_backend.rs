@@ -0,0 +1,369 @@
+// src-tauri/src/link_backend.rs
+
+//! Directory link management behind a `LinkBackend` trait.
+//! Unix uses symlinks and Windows uses junctions; tests swap in an in-memory backend.
+//! File links are symlinks on both, for when a hardlink is not possible.
+
+use std::{
+    fs,
+    io::ErrorKind,
+    path::{Path, PathBuf},
+};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum LinkError {
+    #[error("Failed to link {} -> {}: {message}", link.display(), target.display())]
+    Create {
+        link: PathBuf,
+        target: PathBuf,
+        message: String,
+    },
+
+    #[error("Failed to remove link {}: {message}", link.display())]
+    Remove { link: PathBuf, message: String },
+
+    #[error("Failed to inspect {}: {message}", path.display())]
+    Inspect { path: PathBuf, message: String },
+
+    #[error("Link source is not a directory: {}", .0.display())]
+    SourceNotDirectory(PathBuf),
+
+    #[error("'{}' exists but is not a link or directory. Remove it manually.", .0.display())]
+    Occupied(PathBuf),
+
+    #[error("Failed to back up {}: {message}", path.display())]
+    Backup { path: PathBuf, message: String },
+}
+
+/// Platform primitives for directory links.
+pub trait LinkBackend {
+    /// Creates a link at `link` that points to the directory `target`.
+    fn create_dir_link(&self, target: &Path, link: &Path) -> Result<(), LinkError>;
+    /// Creates a symlink at `link` that points to the file `target`.
+    fn create_file_link(&self, target: &Path, link: &Path) -> Result<(), LinkError>;
+    /// Removes the link itself, never the directory it points to.
+    fn remove_dir_link(&self, link: &Path) -> Result<(), LinkError>;
+    /// Whether `path` is a link. Missing paths are not links.
+    fn is_link(&self, path: &Path) -> Result<bool, LinkError>;
+    fn read_link(&self, link: &Path) -> Result<PathBuf, LinkError>;
+}
+
+/// Symlinks on Unix, junctions on Windows (they need no admin rights).
+pub struct PlatformLinkBackend;
+
+impl LinkBackend for PlatformLinkBackend {
+    fn create_dir_link(&self, target: &Path, link: &Path) -> Result<(), LinkError> {
+        #[cfg(unix)]
+        let result = std::os::unix::fs::symlink(target, link);
+        #[cfg(windows)]
+        let result = junction::create(target, link);
+
+        result.map_err(|e| LinkError::Create {
+            link: link.to_path_buf(),
+            target: target.to_path_buf(),
+            message: e.to_string(),
+        })
+    }
+
+    fn create_file_link(&self, target: &Path, link: &Path) -> Result<(), LinkError> {
+        #[cfg(unix)]
+        let result = std::os::unix::fs::symlink(target, link);
+        // Needs Developer Mode or admin rights, unlike junctions.
+        #[cfg(windows)]
+        let result = std::os::windows::fs::symlink_file(target, link);
+
+        result.map_err(|e| LinkError::Create {
+            link: link.to_path_buf(),
+            target: target.to_path_buf(),
+            message: e.to_string(),
+        })
+    }
+
+    fn remove_dir_link(&self, link: &Path) -> Result<(), LinkError> {
+        #[cfg(unix)]
+        let result = fs::remove_file(link);
+        #[cfg(windows)]
+        let result = junction::delete(link).and_then(|_| fs::remove_dir(link));
+
+        result.map_err(|e| LinkError::Remove {
+            link: link.to_path_buf(),
+            message: e.to_string(),
+        })
+    }
+
+    fn is_link(&self, path: &Path) -> Result<bool, LinkError> {
+        match fs::symlink_metadata(path) {
+            Ok(meta) => Ok(meta.file_type().is_symlink()),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(false),
+            Err(e) => Err(LinkError::Inspect {
+                path: path.to_path_buf(),
+                message: e.to_string(),
+            }),
+        }
+    }
+
+    fn read_link(&self, link: &Path) -> Result<PathBuf, LinkError> {
+        #[cfg(unix)]
+        let result = fs::read_link(link);
+        #[cfg(windows)]
+        let result = junction::get_target(link).or_else(|_| fs::read_link(link));
+
+        result.map_err(|e| LinkError::Inspect {
+            path: link.to_path_buf(),
+            message: e.to_string(),
+        })
+    }
+}
+
+/// Returns the first free `<target>_backup`, `<target>_backup_1`, … path.
+pub fn next_backup_path(target: &Path) -> PathBuf {
+    let base = target.as_os_str().to_string_lossy();
+    let mut backup = PathBuf::from(format!("{}_backup", base));
+    let mut i = 1;
+    while fs::symlink_metadata(&backup).is_ok() {
+        backup = PathBuf::from(format!("{}_backup_{}", base, i));
+        i += 1;
+    }
+    backup
+}
+
+/// Points `link` at `source`. An existing link is replaced; an existing real directory
+/// is renamed to the next free `_backup` path, which is returned.
+pub fn link_folder_with_backup(
+    backend: &impl LinkBackend,
+    source: &Path,
+    link: &Path,
+) -> Result<Option<PathBuf>, LinkError> {
+    if !source.is_dir() {
+        return Err(LinkError::SourceNotDirectory(source.to_path_buf()));
+    }
+
+    let mut backup = None;
+    if backend.is_link(link)? {
+        backend.remove_dir_link(link)?;
+    } else {
+        match fs::symlink_metadata(link) {
+            Ok(m) if m.is_dir() => {
+                let path = next_backup_path(link);
+                fs::rename(link, &path).map_err(|e| LinkError::Backup {
+                    path: link.to_path_buf(),
+                    message: e.to_string(),
+                })?;
+                backup = Some(path);
+            }
+            Ok(_) => return Err(LinkError::Occupied(link.to_path_buf())),
+            Err(e) if e.kind() == ErrorKind::NotFound => {}
+            Err(e) => {
+                return Err(LinkError::Inspect {
+                    path: link.to_path_buf(),
+                    message: e.to_string(),
+                })
+            }
+        }
+    }
+
+    if let Err(e) = backend.create_dir_link(source, link) {
+        // Put the original folder back rather than leave the game without one.
+        if let Some(path) = &backup {
+            if let Err(undo) = fs::rename(path, link) {
+                log::error!(
+                    "[link_backend::link_folder] Failed to restore {} after link error: {}",
+                    path.display(),
+                    undo
+                );
+            }
+        }
+        return Err(e);
+    }
+    Ok(backup)
+}
+
+/// Removes `link` if it is a link. Returns whether anything was removed.
+pub fn remove_link(backend: &impl LinkBackend, link: &Path) -> Result<bool, LinkError> {
+    if backend.is_link(link)? {
+        backend.remove_dir_link(link)?;
+        Ok(true)
+    } else {
+        Ok(false)
+    }
+}
+
+#[cfg(test)]
+pub mod mock {
+    use super::{LinkBackend, LinkError};
+    use std::{
+        collections::HashMap,
+        path::{Path, PathBuf},
+        sync::Mutex,
+    };
+
+    /// In-memory backend: links exist only in its map, so tests never need
+    /// symlink or junction privileges.
+    #[derive(Default)]
+    pub struct MockLinkBackend {
+        pub links: Mutex<HashMap<PathBuf, PathBuf>>,
+        pub fail_create: bool,
+    }
+
+    impl LinkBackend for MockLinkBackend {
+        fn create_dir_link(&self, target: &Path, link: &Path) -> Result<(), LinkError> {
+            if self.fail_create {
+                return Err(LinkError::Create {
+                    link: link.to_path_buf(),
+                    target: target.to_path_buf(),
+                    message: "mock failure".into(),
+                });
+            }
+            self.links
+                .lock()
+                .unwrap()
+                .insert(link.to_path_buf(), target.to_path_buf());
+            Ok(())
+        }
+
+        fn create_file_link(&self, target: &Path, link: &Path) -> Result<(), LinkError> {
+            self.create_dir_link(target, link)
+        }
+
+        fn remove_dir_link(&self, link: &Path) -> Result<(), LinkError> {
+            self.links
+                .lock()
+                .unwrap()
+                .remove(link)
+                .map(|_| ())
+                .ok_or_else(|| LinkError::Remove {
+                    link: link.to_path_buf(),
+                    message: "not a link".into(),
+                })
+        }
+
+        fn is_link(&self, path: &Path) -> Result<bool, LinkError> {
+            Ok(self.links.lock().unwrap().contains_key(path))
+        }
+
+        fn read_link(&self, link: &Path) -> Result<PathBuf, LinkError> {
+            self.links
+                .lock()
+                .unwrap()
+                .get(link)
+                .cloned()
+                .ok_or_else(|| LinkError::Inspect {
+                    path: link.to_path_buf(),
+                    message: "not a link".into(),
+                })
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::mock::MockLinkBackend;
+    use super::*;
+
+    fn temp_dir() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("xlfm-links-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn links_missing_target() {
+        let root = temp_dir();
+        let source = root.join("External");
+        fs::create_dir(&source).unwrap();
+        let link = root.join("Maps");
+        let backend = MockLinkBackend::default();
+
+        let backup = link_folder_with_backup(&backend, &source, &link).unwrap();
+
+        assert_eq!(backup, None);
+        assert_eq!(backend.read_link(&link).unwrap(), source);
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn backs_up_real_directory_with_increasing_suffix() {
+        let root = temp_dir();
+        let source = root.join("External");
+        fs::create_dir(&source).unwrap();
+        let link = root.join("Maps");
+        fs::create_dir(&link).unwrap();
+        fs::create_dir(root.join("Maps_backup")).unwrap();
+        let backend = MockLinkBackend::default();
+
+        let backup = link_folder_with_backup(&backend, &source, &link).unwrap();
+
+        assert_eq!(backup, Some(root.join("Maps_backup_1")));
+        assert!(root.join("Maps_backup_1").is_dir());
+        assert!(!link.exists());
+        assert!(backend.is_link(&link).unwrap());
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn replaces_existing_link() {
+        let root = temp_dir();
+        let (old, new) = (root.join("Old"), root.join("New"));
+        fs::create_dir(&old).unwrap();
+        fs::create_dir(&new).unwrap();
+        let link = root.join("Maps");
+        let backend = MockLinkBackend::default();
+        backend.create_dir_link(&old, &link).unwrap();
+
+        let backup = link_folder_with_backup(&backend, &new, &link).unwrap();
+
+        assert_eq!(backup, None);
+        assert_eq!(backend.read_link(&link).unwrap(), new);
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn restores_backup_when_link_creation_fails() {
+        let root = temp_dir();
+        let source = root.join("External");
+        fs::create_dir(&source).unwrap();
+        let link = root.join("Maps");
+        fs::create_dir(&link).unwrap();
+        let backend = MockLinkBackend {
+            fail_create: true,
+            ..Default::default()
+        };
+
+        let err = link_folder_with_backup(&backend, &source, &link).unwrap_err();
+
+        assert!(matches!(err, LinkError::Create { .. }));
+        assert!(link.is_dir());
+        assert!(!root.join("Maps_backup").exists());
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn rejects_missing_source_and_occupied_target() {
+        let root = temp_dir();
+        let backend = MockLinkBackend::default();
+        let link = root.join("Maps");
+
+        let err = link_folder_with_backup(&backend, &root.join("Nope"), &link).unwrap_err();
+        assert!(matches!(err, LinkError::SourceNotDirectory(_)));
+
+        fs::write(&link, b"not a dir").unwrap();
+        let err = link_folder_with_backup(&backend, &root, &link).unwrap_err();
+        assert!(matches!(err, LinkError::Occupied(_)));
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn remove_link_ignores_real_directories() {
+        let root = temp_dir();
+        let backend = MockLinkBackend::default();
+        assert!(!remove_link(&backend, &root).unwrap());
+        assert!(root.is_dir());
+
+        let link = root.join("Maps");
+        backend.create_dir_link(&root, &link).unwrap();
+        assert!(remove_link(&backend, &link).unwrap());
+        assert!(!backend.is_link(&link).unwrap());
+        fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -2,52 +2,27 @@
 
 //! Contains Tauri commands specifically for map‑related operations (symlinks, listing with thumbnails).
 
+use crate::content_folders::ContentFolder;
+use crate::content_scanner::{check_listing_root, sort_by_name, ContentScanner};
 use crate::error::{CommandError, CommandResult};
+use crate::install_records::{load_install_records, update_installed_path};
+use crate::link_backend::{link_folder_with_backup, remove_link, PlatformLinkBackend};
+use crate::map_libraries::load_library_roots;
+use crate::map_matcher::match_local_map;
+use crate::map_metadata::{metadata_from_modio, sidecar_path, write_sidecar};
 use crate::models::*;
+use crate::settings;
+use crate::state::ModioCatalogState;
 use crate::utils::*;
+use same_file::Handle;
 use std::{
-    collections::{hash_map::DefaultHasher, HashMap, HashSet},
+    collections::HashMap,
     ffi::OsStr,
     fs,
-    hash::{Hash, Hasher},
     path::{Path, PathBuf},
 };
 use tauri::{command, Manager};
 
-// --- Hash helper ---
-fn hash_path(path: &Path) -> String {
-    let path_str = path.to_string_lossy();
-    let mut hasher = DefaultHasher::new();
-    path_str.hash(&mut hasher);
-    format!("{:x}", hasher.finish())
-}
-
-/// Copies a thumbnail into the app cache and returns its new path.
-fn cache_thumbnail_if_needed(
-    original_thumbnail_path: &Path,
-    original_map_path: &Path,
-    app_handle: &tauri::AppHandle,
-) -> Option<PathBuf> {
-    let base_cache_dir = app_handle.path().app_cache_dir().ok()?;
-    let cache_dir = base_cache_dir.join("thumbnails");
-    if !cache_dir.exists() {
-        fs::create_dir_all(&cache_dir).ok()?;
-    }
-
-    let ext = original_thumbnail_path
-        .extension()
-        .and_then(|e| e.to_str())
-        .unwrap_or_default();
-    let cache_filename = format!("{}.{}", hash_path(original_map_path), ext);
-    let cached_thumb_path = cache_dir.join(&cache_filename);
-
-    if !cached_thumb_path.exists() {
-        fs::copy(original_thumbnail_path, &cached_thumb_path).ok()?;
-    }
-
-    Some(cached_thumb_path)
-}
-
 #[command]
 pub fn is_symlink(path: String) -> Result<bool, String> {
     let p = Path::new(&path);
@@ -62,64 +37,142 @@ pub fn is_symlink(path: String) -> Result<bool, String> {
 }
 
 /// Removes a symlink or junction at the given path.
-fn platform_remove_symlink(link_path_str: &str) -> CommandResult<bool> {
-    let target = Path::new(link_path_str);
-    match fs::symlink_metadata(target) {
-        Ok(meta) if meta.file_type().is_symlink() => {
-            #[cfg(unix)]
-            fs::remove_file(target)?;
-            #[cfg(windows)]
-            junction::delete(target)
-                .map_err(|e| CommandError::Symlink(format!("Failed to delete junction: {}", e)))?;
-            Ok(true)
-        }
-        Ok(_) => Ok(false),
-        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(false),
-        Err(e) => Err(e.into()),
-    }
+pub(crate) fn platform_remove_symlink(link_path_str: &str) -> CommandResult<bool> {
+    Ok(remove_link(&PlatformLinkBackend, Path::new(link_path_str))?)
 }
 
 #[command]
 pub fn create_maps_symlink(new_folder: String, target_link: String) -> CommandResult<()> {
-    let source = Path::new(&new_folder);
-    let target = Path::new(&target_link);
-    if !source.is_dir() {
+    let backup = link_folder_with_backup(
+        &PlatformLinkBackend,
+        Path::new(&new_folder),
+        Path::new(&target_link),
+    )?;
+    if let Some(backup) = backup {
+        log::info!(
+            "[map_commands::create_maps_symlink] Moved existing folder to {}",
+            backup.display()
+        );
+    }
+    Ok(())
+}
+
+/// Suffix of the folder that holds disabled maps, next to the real Maps folder.
+pub(crate) const DISABLED_SUFFIX: &str = "_disabled";
+
+/// Returns the `<Maps>_disabled` store for a maps folder. Symlinks are resolved first so
+/// the store sits on the same volume as the maps and moves stay atomic renames.
+pub fn disabled_store_for(maps_folder: &Path) -> Option<PathBuf> {
+    let real = fs::canonicalize(maps_folder).ok()?;
+    let name = real.file_name()?.to_string_lossy().to_string();
+    Some(real.with_file_name(format!("{}{}", name, DISABLED_SUFFIX)))
+}
+
+/// Files that belong to a single-file map and must travel with it: its sidecar and
+/// same-stem thumbnail images.
+fn map_companions(map_path: &Path) -> Vec<PathBuf> {
+    let mut companions = vec![sidecar_path(map_path)];
+    if map_path.is_file() {
+        if let (Some(parent), Some(stem)) = (
+            map_path.parent(),
+            map_path.file_stem().and_then(OsStr::to_str),
+        ) {
+            for ext in THUMBNAIL_EXTS.iter() {
+                companions.push(parent.join(format!("{}.{}", stem, ext)));
+            }
+        }
+    }
+    companions.retain(|p| p.is_file() && p != map_path);
+    companions
+}
+
+/// Moves a map and its companions into `dest_dir`, undoing completed moves if one fails.
+fn move_map_with_companions(map_path: &Path, dest_dir: &Path) -> CommandResult<PathBuf> {
+    let file_name = map_path
+        .file_name()
+        .ok_or_else(|| CommandError::Input(format!("Invalid map path: {}", map_path.display())))?;
+    let dest = dest_dir.join(file_name);
+    if fs::symlink_metadata(&dest).is_ok() {
         return Err(CommandError::Input(format!(
-            "Source not a directory: {}",
-            new_folder
+            "A map named '{}' already exists in {}",
+            file_name.to_string_lossy(),
+            dest_dir.display()
         )));
     }
+    fs::create_dir_all(dest_dir)?;
 
-    match fs::symlink_metadata(target) {
-        Ok(m) if m.file_type().is_symlink() => {
-            let _ = platform_remove_symlink(&target_link)?;
-        }
-        Ok(m) if m.is_dir() => {
-            let mut backup = format!("{}_backup", &target_link);
-            let mut i = 1;
-            while Path::new(&backup).exists() {
-                backup = format!("{}_backup_{}", &target_link, i);
-                i += 1;
+    let companions = map_companions(map_path);
+    let mut moved: Vec<(PathBuf, PathBuf)> = Vec::new();
+    let moves = std::iter::once(map_path.to_path_buf()).chain(companions);
+    for from in moves {
+        let to = dest_dir.join(from.file_name().unwrap_or_default());
+        if let Err(e) = fs::rename(&from, &to) {
+            for (orig, done) in moved.iter().rev() {
+                if let Err(undo) = fs::rename(done, orig) {
+                    log::error!(
+                        "[map_commands::move_map] Rollback failed for {}: {}",
+                        done.display(),
+                        undo
+                    );
+                }
             }
-            fs::rename(target, &backup)?;
-        }
-        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
-        Err(e) => return Err(e.into()),
-        Ok(_) => {
-            return Err(CommandError::Input(format!(
-                "Target '{}' exists but is not a symlink or dir. Remove it manually.",
-                target_link
-            )))
+            return Err(CommandError::Io(format!(
+                "Failed to move {} to {}: {}",
+                from.display(),
+                to.display(),
+                e
+            )));
         }
+        moved.push((from, to));
     }
+    Ok(dest)
+}
 
-    #[cfg(unix)]
-    std::os::unix::fs::symlink(source, target)?;
-    #[cfg(windows)]
-    junction::create(source, target)
-        .map_err(|e| CommandError::Symlink(format!("Windows junction failed: {}", e)))?;
+/// Moves a map out of the game's Maps folder into the parallel disabled store.
+#[command]
+pub fn disable_map(app_handle: tauri::AppHandle, map_path: String) -> CommandResult<String> {
+    let path = Path::new(&map_path);
+    let maps_folder = path
+        .parent()
+        .ok_or_else(|| CommandError::Input(format!("Invalid map path: {}", map_path)))?;
+    if maps_folder
+        .file_name()
+        .and_then(OsStr::to_str)
+        .is_some_and(|name| name.ends_with(DISABLED_SUFFIX))
+    {
+        return Err(CommandError::Input(format!("Map is already disabled: {}", map_path)));
+    }
+    let store = disabled_store_for(maps_folder).ok_or_else(|| {
+        CommandError::DirectoryResolution(format!(
+            "Could not resolve disabled store for {}",
+            maps_folder.display()
+        ))
+    })?;
 
-    Ok(())
+    let dest = move_map_with_companions(path, &store)?;
+    update_installed_path(&app_handle, path, &dest);
+    log::info!("[map_commands::disable_map] {} -> {}", path.display(), dest.display());
+    Ok(dest.to_string_lossy().into_owned())
+}
+
+/// Moves a disabled map back into the Maps folder next to its disabled store.
+#[command]
+pub fn enable_map(app_handle: tauri::AppHandle, map_path: String) -> CommandResult<String> {
+    let path = Path::new(&map_path);
+    let store = path
+        .parent()
+        .ok_or_else(|| CommandError::Input(format!("Invalid map path: {}", map_path)))?;
+    let maps_folder = store
+        .file_name()
+        .and_then(OsStr::to_str)
+        .and_then(|name| name.strip_suffix(DISABLED_SUFFIX))
+        .map(|name| store.with_file_name(name))
+        .ok_or_else(|| CommandError::Input(format!("Map is not disabled: {}", map_path)))?;
+
+    let dest = move_map_with_companions(path, &maps_folder)?;
+    update_installed_path(&app_handle, path, &dest);
+    log::info!("[map_commands::enable_map] {} -> {}", path.display(), dest.display());
+    Ok(dest.to_string_lossy().into_owned())
 }
 
 #[command]
@@ -131,267 +184,137 @@ pub fn remove_maps_symlink(link_path_str: String) -> CommandResult<()> {
 #[command]
 pub fn list_local_maps(
     app_handle: tauri::AppHandle,
-    relative_maps_path: String,
+    folder: Option<String>,
 ) -> CommandResult<DirectoryListingResult> {
     let maps_folder_path =
-        resolve_document_path(&relative_maps_path).map_err(CommandError::DirectoryResolution)?;
+        settings::resolve_content_root(&app_handle, folder, ContentFolder::Maps)?;
 
-    if !maps_folder_path.exists() {
+    if !check_listing_root(&maps_folder_path)? {
         return Ok(DirectoryListingResult {
             status: ListingStatus::DoesNotExist,
             entries: Vec::new(),
             path: maps_folder_path,
         });
     }
-    if !maps_folder_path.is_dir() {
-        return Err(CommandError::Input(format!(
-            "Path exists but is not a directory: {}",
-            maps_folder_path.display()
-        )));
-    }
-
-    let mut map_entries = Vec::new();
-    let mut thumbnail_map: HashMap<String, (PathBuf, String)> = HashMap::new();
-    let mut is_empty = true;
-
-    // --- First Pass: Check for emptiness and find thumbnails ---
-    let mut dir_reader_peek = match fs::read_dir(&maps_folder_path) {
-        Ok(reader) => reader.peekable(),
-        Err(e) => {
-            return Err(CommandError::Io(format!(
-                "Failed read dir (peek) {}: {}",
-                maps_folder_path.display(),
-                e
-            )))
-        }
-    };
 
-    if dir_reader_peek.peek().is_some() {
-        is_empty = false;
-        log::info!(
-            "[map_commands::list_local_maps] Directory not empty. Scanning for thumbnails..."
-        );
-        // (Keep your existing thumbnail discovery logic here)
-        let dir_reader_thumbs = fs::read_dir(&maps_folder_path).map_err(|e| {
-            CommandError::Io(format!(
-                "Failed read dir (thumbs) {}: {}",
-                maps_folder_path.display(),
-                e
-            ))
-        })?;
-        for entry_result in dir_reader_thumbs {
-            match entry_result {
-                   Ok(entry) => {
-                        let path = entry.path();
-                        let file_name_os = entry.file_name();
-                        let file_name_str = file_name_os.to_string_lossy();
-                        if path.is_dir() {
-                             let dir_name_lower = file_name_str.to_lowercase();
-                             let mut found_thumbnail = false;
-                             for ext in THUMBNAIL_EXTS.iter() {
-                                  let candidate_path = path.join(format!("{}.{}", file_name_str, ext));
-                                  if candidate_path.is_file() {
-                                       if let Some(mime) = get_mime_type_from_extension(ext) {
-                                             thumbnail_map.insert(dir_name_lower.clone(), (candidate_path, mime));
-                                             found_thumbnail = true;
-                                             break;
-                                       }
-                                  }
-                             }
-                             if !found_thumbnail {
-                                  if let Ok(sub_entries) = fs::read_dir(&path) {
-                                       for sub_res in sub_entries {
-                                            if let Ok(sub_entry) = sub_res {
-                                                 let sub_path = sub_entry.path();
-                                                 if sub_path.is_file() {
-                                                      if let Some(ext) = sub_path.extension().and_then(OsStr::to_str) {
-                                                           if THUMBNAIL_EXTS.contains(ext.to_lowercase().as_str()) {
-                                                                if let Some(mime) = get_mime_type_from_extension(ext) {
-                                                                     thumbnail_map.insert(dir_name_lower.clone(), (sub_path, mime));
-                                                                     break;
-                                                                }
-                                                           }
-                                                      }
-                                                 }
-                                            }
-                                       }
-                                  }
-                             }
-                        } else if path.is_file() {
-                             if let Some(ext) = path.extension().and_then(OsStr::to_str) {
-                                  if THUMBNAIL_EXTS.contains(ext.to_lowercase().as_str()) {
-                                       if let Some(mime) = get_mime_type_from_extension(ext) {
-                                             if let Some(stem) = path.file_stem().and_then(OsStr::to_str) {
-                                                  let key = stem.to_lowercase();
-                                                  thumbnail_map.entry(key).or_insert((path.clone(), mime));
-                                             }
-                                       }
-                                  }
-                             }
-                        }
-                   }
-                   Err(e) => log::error!(
-                        "[map_commands::list_local_maps] Error reading directory entry during thumbnail scan: {}", e
-                   ),
-              }
-        }
-    } else {
-        log::info!("[map_commands::list_local_maps] Directory is empty.");
+    let mut scanner = ContentScanner::for_app(&app_handle, ContentFolder::Maps);
+    let (is_empty, mut map_entries) = scanner.scan_folder(&maps_folder_path)?;
+    for entry in map_entries.iter_mut() {
+        entry.enabled = Some(true);
     }
 
-    // --- NEW: Set to store valid cached thumbnail filenames ---
-    let mut valid_cached_filenames: HashSet<String> = HashSet::new();
-
-    // --- Second Pass: Collect map entries ---
-    log::info!("[map_commands::list_local_maps] Collecting final map entries...");
-    if !is_empty {
-        let dir_reader_entries = fs::read_dir(&maps_folder_path).map_err(|e| {
-            CommandError::Io(format!(
-                "Failed read dir (entries) {}: {}",
-                maps_folder_path.display(),
-                e
-            ))
-        })?;
-
-        for entry_result in dir_reader_entries {
-            match entry_result {
-                Ok(entry) => {
-                    let path = entry.path(); // path = original map path
-                    match entry.metadata() {
-                        Ok(metadata) => {
-                            let name: Option<String> = entry.file_name().to_str().map(String::from);
-                            let is_directory = metadata.is_dir();
-                            if path.as_os_str().is_empty() || (name.is_none() && !is_directory) {
-                                continue;
-                            }
-                            let should_include = if !is_directory {
-                                match path.extension().and_then(OsStr::to_str) {
-                                    Some(ext) => !EXCLUDED_FILE_EXTS.contains(ext.to_lowercase().as_str()),
-                                    None => true,
-                                }
-                            } else {
-                                true
-                            };
-
-                            if should_include {
-                                let size = if is_directory {
-                                    Some(calculate_directory_size(&path))
-                                } else {
-                                    Some(metadata.len())
-                                };
-                                let modified_time = system_time_to_millis(metadata.modified().ok())
-                                    .or_else(|| system_time_to_millis(metadata.created().ok()));
-                                let key = if is_directory {
-                                    name.clone().map(|n| n.to_lowercase())
-                                } else {
-                                    path.file_stem().and_then(OsStr::to_str).map(str::to_lowercase)
-                                };
-
-                                let mut final_cached_thumb_path: Option<PathBuf> = None;
-                                let mut final_thumb_mime: Option<String> = None;
-
-                                if let Some((original_thumb_path, original_thumb_mime)) =
-                                    key.and_then(|k| thumbnail_map.get(&k))
-                                {
-                                    // --- MODIFIED: Store valid cached filename ---
-                                    if let Some(cached_path) = cache_thumbnail_if_needed(
-                                        original_thumb_path,
-                                        &path,
-                                        &app_handle,
-                                    ) {
-                                        if let Some(filename_osstr) = cached_path.file_name() {
-                                            // Convert OsStr to String, handle potential lossy conversion if needed
-                                            valid_cached_filenames.insert(filename_osstr.to_string_lossy().to_string());
-                                        }
-                                        final_cached_thumb_path = Some(cached_path); // Assign the path for FsEntry
-                                    }
-                                    // --- END MODIFIED ---
-                                    final_thumb_mime = Some(original_thumb_mime.clone());
-                                }
+    // --- Disabled maps live in a parallel `<Maps>_disabled` store ---
+    if let Some(store) = disabled_store_for(&maps_folder_path).filter(|p| p.is_dir()) {
+        let (_, disabled_entries) = scanner.scan_folder(&store)?;
+        map_entries.extend(disabled_entries.into_iter().map(|mut entry| {
+            entry.enabled = Some(false);
+            entry
+        }));
+    }
 
-                                map_entries.push(FsEntry {
-                                    name,
-                                    path,
-                                    is_directory,
-                                    size,
-                                    modified: modified_time,
-                                    thumbnail_path: final_cached_thumb_path, // Use cached path
-                                    thumbnail_mime_type: final_thumb_mime,
-                                });
+    // --- Maps from registered library roots, tagged with the root they live in ---
+    // Linked maps are matched to their library entry by file identity, which catches
+    // hardlinked files as well as directory links.
+    let roots = load_library_roots(&app_handle);
+    if !roots.is_empty() {
+        let linked: HashMap<Handle, usize> = map_entries
+            .iter()
+            .enumerate()
+            .filter_map(|(i, entry)| Some((Handle::from_path(&entry.path).ok()?, i)))
+            .collect();
+        let mut library_entries = Vec::new();
+        for root in &roots {
+            if !root.is_dir() {
+                log::warn!(
+                    "[map_commands::list_local_maps] Library root unavailable: {}",
+                    root.display()
+                );
+                continue;
+            }
+            match scanner.scan_folder(root) {
+                Ok((_, entries)) => {
+                    for mut e in entries {
+                        let link = Handle::from_path(&e.path)
+                            .ok()
+                            .and_then(|h| linked.get(&h).copied());
+                        match link {
+                            Some(i) => map_entries[i].library_root = Some(root.clone()),
+                            None => {
+                                e.library_root = Some(root.clone());
+                                library_entries.push(e);
                             }
                         }
-                        Err(e) => log::error!(
-                            "[map_commands::list_local_maps] Error getting metadata for {}: {}",
-                            path.display(), e
-                        ),
                     }
                 }
-                Err(e) => log::error!(
-                    "[map_commands::list_local_maps] Error reading directory entry during final scan: {}", e
+                Err(e) => log::warn!(
+                    "[map_commands::list_local_maps] Failed to scan library root {}: {}",
+                    root.display(),
+                    e
                 ),
             }
         }
+        map_entries.extend(library_entries);
     }
 
-    // --- Sort entries (remains the same) ---
-    map_entries.sort_by(|a, b| {
-        a.name
-            .as_deref()
-            .unwrap_or("")
-            .to_lowercase()
-            .cmp(&b.name.as_deref().unwrap_or("").to_lowercase())
-    });
+    sort_by_name(&mut map_entries);
+
+    // --- Link entries to the mod.io catalog ---
+    {
+        let records = load_install_records(&app_handle);
+        let catalog_state = app_handle.state::<ModioCatalogState>();
+        let catalog = catalog_state.mods.lock().unwrap();
+        for entry in map_entries.iter_mut() {
+            entry.modio_match = match_local_map(entry, &catalog, &records);
+
+            // Maps with no image of their own and no embedded preview show the mod.io logo.
+            if let Some(ModioMatch { mod_id, .. }) = entry.modio_match {
+                let logo = catalog
+                    .iter()
+                    .find(|m| m.id == mod_id)
+                    .and_then(|m| m.logo.as_ref())
+                    .and_then(|logo| logo.preview_url());
+                if let Some(url) = logo {
+                    scanner.apply_logo(entry, mod_id, url);
+                }
+            }
 
-    // --- NEW: Cache Pruning Logic ---
-    log::info!("[map_commands::list_local_maps] Pruning thumbnail cache...");
-    match app_handle.path().app_cache_dir() {
-        Ok(cache_base_dir) => {
-            let subfolder = if maps_folder_path.to_string_lossy().to_lowercase().contains("mods") {
-                "mods"
-            } else {
-                "maps"
-            };
-            let thumbnail_cache_dir = cache_base_dir.join("thumbnails").join(subfolder);
-            if thumbnail_cache_dir.is_dir() {
-                // Check if it IS a directory
-                match fs::read_dir(&thumbnail_cache_dir) {
-                    Ok(cache_entries) => {
-                        for entry_res in cache_entries {
-                            if let Ok(cache_entry) = entry_res {
-                                let cached_file_path = cache_entry.path();
-                                // Check if it's a file to avoid trying to remove subdirs
-                                if cached_file_path.is_file() {
-                                     if let Some(filename_osstr) = cached_file_path.file_name() {
-                                          let filename_str = filename_osstr.to_string_lossy().to_string();
-                                          // Check if this cached file is NOT in our set of valid files
-                                          if !valid_cached_filenames.contains(&filename_str) {
-                                               log::debug!("[map_commands::list_local_maps] Pruning orphaned cache file: {}", cached_file_path.display());
-                                               match fs::remove_file(&cached_file_path) {
-                                                    Ok(_) => log::info!("[map_commands::list_local_maps] Successfully pruned: {}", cached_file_path.display()),
-                                                    Err(e) => log::warn!("[map_commands::list_local_maps] Failed to prune cache file {}: {}", cached_file_path.display(), e),
-                                               }
-                                          }
-                                     }
-                                }
-                           } else {
-                                log::warn!("[map_commands::list_local_maps] Failed to read entry in cache directory during prune");
-                           }
+            // Backfill sidecars for catalog installs made before sidecars existed.
+            if entry.metadata.is_none() {
+                if let Some(ModioMatch {
+                    mod_id,
+                    method: MatchMethod::InstallRecord,
+                    ..
+                }) = entry.modio_match
+                {
+                    if let Some(m) = catalog.iter().find(|m| m.id == mod_id) {
+                        let installed_at = records
+                            .iter()
+                            .find(|r| r.installed_path == entry.path)
+                            .and_then(|r| r.installed_at);
+                        let metadata = metadata_from_modio(m, installed_at, None);
+                        match write_sidecar(&entry.path, &metadata) {
+                            Ok(()) => entry.metadata = Some(metadata),
+                            Err(e) => log::warn!(
+                                "[map_commands::list_local_maps] Failed to backfill sidecar for {}: {}",
+                                entry.path.display(),
+                                e
+                            ),
                         }
                     }
-                    Err(e) => log::warn!("[map_commands::list_local_maps] Failed read cache directory for pruning: {}", e),
                 }
-            } else {
-                log::debug!("[map_commands::list_local_maps] Thumbnail cache directory not found or not a directory, skipping prune: {}", thumbnail_cache_dir.display());
             }
         }
-        Err(e) => log::warn!(
-            "[map_commands::list_local_maps] Could not resolve app cache directory for pruning: {}",
-            e
-        ),
+        log::info!(
+            "[map_commands::list_local_maps] Matched {} of {} entries against {} catalog mods",
+            map_entries.iter().filter(|e| e.modio_match.is_some()).count(),
+            map_entries.len(),
+            catalog.len()
+        );
     }
-    // --- END Cache Pruning Logic ---
 
-    let final_status = if is_empty {
+    scanner.finish();
+
+    let final_status = if is_empty && map_entries.is_empty() {
         ListingStatus::ExistsAndEmpty
     } else {
         ListingStatus::ExistsAndPopulated
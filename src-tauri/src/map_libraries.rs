@@ -0,0 +1,352 @@
+// src-tauri/src/map_libraries.rs
+
+//! Map library roots: folders on any drive that hold maps outside the game's Maps folder.
+//! Listings merge every root into one view; chosen maps are linked into Maps one by one,
+//! so the game still only sees a single folder.
+
+use crate::content_folders::ContentFolder;
+use crate::error::{CommandError, CommandResult};
+use crate::link_backend::{LinkBackend, PlatformLinkBackend};
+use crate::map_profiles::{is_plain_entry_name, link_map_entry};
+use crate::settings;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+use tauri::{command, AppHandle, Manager};
+
+const LIBRARIES_FILE: &str = "map_libraries.json";
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct LibraryMapRef {
+    pub root: PathBuf,
+    /// Entry name (folder or file name) inside the root.
+    pub name: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct MapLibrariesConfig {
+    /// Canonical paths, so entries can be attributed with `starts_with`.
+    #[serde(default)]
+    pub roots: Vec<PathBuf>,
+    /// Links XLFM created in the Maps folder, so they can be removed again.
+    #[serde(default)]
+    pub materialized: Vec<LibraryMapRef>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct MaterializeResult {
+    pub maps_path: PathBuf,
+    pub linked: Vec<String>,
+    pub removed: Vec<String>,
+    /// Selected maps that no longer exist in their root.
+    pub missing: Vec<String>,
+    /// Selected maps whose name is already taken in the Maps folder.
+    pub conflicts: Vec<String>,
+    /// Maps that could not be linked or unlinked, as `name: reason`.
+    #[serde(default)]
+    pub errors: Vec<String>,
+}
+
+fn libraries_file(app_handle: &AppHandle) -> CommandResult<PathBuf> {
+    app_handle
+        .path()
+        .app_data_dir()
+        .map(|dir| dir.join(LIBRARIES_FILE))
+        .map_err(|e| CommandError::DirectoryResolution(e.to_string()))
+}
+
+pub fn load_libraries(app_handle: &AppHandle) -> CommandResult<MapLibrariesConfig> {
+    let path = libraries_file(app_handle)?;
+    match fs::read(&path) {
+        Ok(bytes) => serde_json::from_slice(&bytes).map_err(|e| {
+            CommandError::Input(format!("Corrupt libraries file {}: {}", path.display(), e))
+        }),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(MapLibrariesConfig::default()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+fn save_libraries(app_handle: &AppHandle, config: &MapLibrariesConfig) -> CommandResult<()> {
+    let path = libraries_file(app_handle)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let json = serde_json::to_vec_pretty(config)
+        .map_err(|e| CommandError::Input(format!("Failed to serialize libraries: {}", e)))?;
+    fs::write(&path, json)?;
+    Ok(())
+}
+
+/// Registered roots; an unreadable config just means no extra roots.
+pub fn load_library_roots(app_handle: &AppHandle) -> Vec<PathBuf> {
+    match load_libraries(app_handle) {
+        Ok(config) => config.roots,
+        Err(e) => {
+            log::warn!("[map_libraries::load_library_roots] {}", e);
+            Vec::new()
+        }
+    }
+}
+
+/// Whether a hardlinked file has another name besides `path`.
+#[cfg(unix)]
+fn has_other_links(path: &Path) -> bool {
+    use std::os::unix::fs::MetadataExt;
+    fs::symlink_metadata(path).is_ok_and(|m| m.nlink() > 1)
+}
+
+#[cfg(not(unix))]
+fn has_other_links(_path: &Path) -> bool {
+    // The link count needs an unstable API here; the library-side check has to do.
+    true
+}
+
+/// Removes a link XLFM put in the Maps folder for `source`. Real folders, and hardlinked
+/// files whose library copy is gone, are left alone.
+fn remove_materialized(
+    backend: &impl LinkBackend,
+    path: &Path,
+    source: &Path,
+) -> CommandResult<bool> {
+    let Ok(meta) = fs::symlink_metadata(path) else {
+        return Ok(false);
+    };
+    let is_link = backend.is_link(path)?;
+    if fs::metadata(path).map(|m| m.is_dir()).unwrap_or(false) {
+        if !is_link {
+            log::warn!(
+                "[map_libraries::materialize] {} is now a real folder, leaving it",
+                path.display()
+            );
+            return Ok(false);
+        }
+        backend.remove_dir_link(path)?;
+        return Ok(true);
+    }
+    // A hardlink is a full copy: deleting it is only safe while the library has its own.
+    let library_has_copy =
+        meta.file_type().is_symlink() || (source.is_file() && has_other_links(path));
+    if !library_has_copy {
+        log::warn!(
+            "[map_libraries::materialize] {} is the only copy left, leaving it",
+            path.display()
+        );
+        return Ok(false);
+    }
+    fs::remove_file(path)?;
+    Ok(true)
+}
+
+#[command]
+pub fn list_map_library_roots(app_handle: AppHandle) -> CommandResult<Vec<PathBuf>> {
+    Ok(load_libraries(&app_handle)?.roots)
+}
+
+#[command]
+pub fn add_map_library_root(app_handle: AppHandle, path: String) -> CommandResult<Vec<PathBuf>> {
+    let root = fs::canonicalize(&path).map_err(|e| {
+        CommandError::Input(format!("Library root is not accessible: {} ({})", path, e))
+    })?;
+    if !root.is_dir() {
+        return Err(CommandError::Input(format!(
+            "Library root is not a directory: {}",
+            path
+        )));
+    }
+    let mut config = load_libraries(&app_handle)?;
+    if let Some(other) = config
+        .roots
+        .iter()
+        .find(|r| root.starts_with(r) || r.starts_with(&root))
+    {
+        return Err(CommandError::Input(format!(
+            "{} overlaps the existing library root {}",
+            root.display(),
+            other.display()
+        )));
+    }
+    log::info!("[map_libraries::add_root] {}", root.display());
+    config.roots.push(root);
+    save_libraries(&app_handle, &config)?;
+    Ok(config.roots)
+}
+
+/// Forgets a root. Maps already linked from it stay until the next materialize.
+#[command]
+pub fn remove_map_library_root(
+    app_handle: AppHandle,
+    path: String,
+) -> CommandResult<Vec<PathBuf>> {
+    let mut config = load_libraries(&app_handle)?;
+    let requested = PathBuf::from(&path);
+    let canonical = fs::canonicalize(&requested).unwrap_or_else(|_| requested.clone());
+    config
+        .roots
+        .retain(|r| *r != requested && *r != canonical);
+    save_libraries(&app_handle, &config)?;
+    Ok(config.roots)
+}
+
+/// Makes the Maps folder contain exactly the chosen library maps (plus any real maps).
+/// Links from an earlier call that are no longer chosen are removed.
+#[command]
+pub fn materialize_library_maps(
+    app_handle: AppHandle,
+    folder: Option<String>,
+    maps: Vec<LibraryMapRef>,
+) -> CommandResult<MaterializeResult> {
+    let maps_path =
+        settings::resolve_content_root(&app_handle, folder, ContentFolder::Maps)?;
+    if !maps_path.is_dir() {
+        return Err(CommandError::Input(format!(
+            "Maps folder does not exist: {}",
+            maps_path.display()
+        )));
+    }
+    let mut config = load_libraries(&app_handle)?;
+    let real_maps = fs::canonicalize(&maps_path)?;
+    if let Some(root) = config
+        .roots
+        .iter()
+        .find(|r| real_maps.starts_with(r) || r.starts_with(&real_maps))
+    {
+        return Err(CommandError::Input(format!(
+            "The Maps folder overlaps the library root {}; link it to a real folder first",
+            root.display()
+        )));
+    }
+    if let Some(bad) = maps.iter().find(|m| !is_plain_entry_name(&m.name)) {
+        return Err(CommandError::Input(format!(
+            "Map '{}' must be a plain entry name inside its library root",
+            bad.name
+        )));
+    }
+    if let Some(bad) = maps.iter().find(|m| !config.roots.contains(&m.root)) {
+        return Err(CommandError::Input(format!(
+            "{} is not a registered library root",
+            bad.root.display()
+        )));
+    }
+
+    let mut result = MaterializeResult {
+        maps_path: maps_path.clone(),
+        ..Default::default()
+    };
+
+    // Errors are collected per map so links already made are always recorded.
+    // --- Drop links that are no longer wanted ---
+    let mut kept = Vec::new();
+    for old in std::mem::take(&mut config.materialized) {
+        if maps.contains(&old) {
+            kept.push(old);
+            continue;
+        }
+        let source = old.root.join(&old.name);
+        match remove_materialized(&PlatformLinkBackend, &maps_path.join(&old.name), &source) {
+            Ok(true) => result.removed.push(old.name),
+            Ok(false) => {}
+            Err(e) => {
+                result.errors.push(format!("{}: {}", old.name, e));
+                kept.push(old);
+            }
+        }
+    }
+
+    // --- Link the selection ---
+    for map in &maps {
+        let dest = maps_path.join(&map.name);
+        let already_linked = kept.contains(map) && fs::symlink_metadata(&dest).is_ok();
+        if already_linked {
+            result.linked.push(map.name.clone());
+            continue;
+        }
+        kept.retain(|k| k != map);
+        let source = map.root.join(&map.name);
+        if !source.exists() {
+            result.missing.push(map.name.clone());
+            continue;
+        }
+        if fs::symlink_metadata(&dest).is_ok() {
+            result.conflicts.push(map.name.clone());
+            continue;
+        }
+        match link_map_entry(&PlatformLinkBackend, &source, &dest) {
+            Ok(()) => {
+                kept.push(map.clone());
+                result.linked.push(map.name.clone());
+            }
+            Err(e) => result.errors.push(format!("{}: {}", map.name, e)),
+        }
+    }
+
+    config.materialized = kept;
+    save_libraries(&app_handle, &config)?;
+    log::info!(
+        "[map_libraries::materialize] {} linked, {} removed, {} missing, {} conflicts, {} errors",
+        result.linked.len(),
+        result.removed.len(),
+        result.missing.len(),
+        result.conflicts.len(),
+        result.errors.len()
+    );
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::link_backend::mock::MockLinkBackend;
+
+    fn temp_dir() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("xlfm-libraries-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn removes_hardlinks_only_while_the_library_copy_exists() {
+        let root = temp_dir();
+        let source = root.join("Library").join("Park.map");
+        let maps = root.join("Maps");
+        fs::create_dir_all(source.parent().unwrap()).unwrap();
+        fs::create_dir_all(&maps).unwrap();
+        fs::write(&source, b"map").unwrap();
+        let backend = MockLinkBackend::default();
+
+        let dest = maps.join("Park.map");
+        link_map_entry(&backend, &source, &dest).unwrap();
+        assert!(remove_materialized(&backend, &dest, &source).unwrap());
+        assert!(!dest.exists());
+        assert!(source.is_file());
+
+        link_map_entry(&backend, &source, &dest).unwrap();
+        fs::remove_file(&source).unwrap();
+        assert!(!remove_materialized(&backend, &dest, &source).unwrap());
+        assert_eq!(fs::read(&dest).unwrap(), b"map");
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn removes_directory_links_but_not_real_folders() {
+        let root = temp_dir();
+        let source = root.join("Library").join("Park");
+        fs::create_dir_all(&source).unwrap();
+        let backend = MockLinkBackend::default();
+
+        let dest = root.join("Maps").join("Park");
+        link_map_entry(&backend, &source, &dest).unwrap();
+        // The mock keeps links in memory, so give the link a directory to stat.
+        fs::create_dir_all(&dest).unwrap();
+        assert!(remove_materialized(&backend, &dest, &source).unwrap());
+        assert!(!backend.is_link(&dest).unwrap());
+
+        assert!(!remove_materialized(&backend, &dest, &source).unwrap());
+        assert!(dest.is_dir());
+        fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -0,0 +1,264 @@
+// src-tauri/src/map_matcher.rs
+
+//! Links local map entries to mod.io catalog entries.
+//!
+//! Signals, strongest first: an install record for the exact path, a mod.io ID in the
+//! map's sidecar, the modfile's filename, then fuzzy name similarity. A matching
+//! uncompressed size nudges confidence up.
+
+use crate::install_records::InstallRecord;
+use crate::models::{FsEntry, MatchMethod, ModioMatch};
+use crate::modio_catalog::ModioMod;
+use std::{collections::HashSet, ffi::OsStr, path::Path};
+
+/// Name similarity (0..1) needed before a fuzzy match is considered at all.
+const MIN_SIMILARITY: f64 = 0.75;
+/// Relative size difference still treated as "the same size".
+const SIZE_TOLERANCE: f64 = 0.05;
+
+/// Lowercases, drops punctuation and version tokens like `v2`, and joins the remaining words.
+pub fn normalize_name(name: &str) -> String {
+    name.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|token| !token.is_empty())
+        .filter(|token| {
+            let digits = token.strip_prefix('v').unwrap_or("");
+            digits.is_empty() || !digits.chars().all(|c| c.is_ascii_digit())
+        })
+        .collect()
+}
+
+fn bigrams(s: &str) -> HashSet<(char, char)> {
+    let chars: Vec<char> = s.chars().collect();
+    chars.windows(2).map(|w| (w[0], w[1])).collect()
+}
+
+/// Sørensen–Dice coefficient over character bigrams of two normalized names.
+pub fn name_similarity(a: &str, b: &str) -> f64 {
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+    if a == b {
+        return 1.0;
+    }
+    let (ba, bb) = (bigrams(a), bigrams(b));
+    if ba.is_empty() || bb.is_empty() {
+        return 0.0;
+    }
+    let shared = ba.intersection(&bb).count();
+    (2 * shared) as f64 / (ba.len() + bb.len()) as f64
+}
+
+fn sizes_match(local: Option<u64>, catalog: &ModioMod) -> bool {
+    let Some(local) = local.filter(|s| *s > 0) else {
+        return false;
+    };
+    let Some(remote) = catalog
+        .modfile
+        .as_ref()
+        .and_then(|f| f.filesize_uncompressed.or(f.filesize))
+        .filter(|s| *s > 0)
+    else {
+        return false;
+    };
+    (local as f64 - remote as f64).abs() / remote as f64 <= SIZE_TOLERANCE
+}
+
+fn entry_key(entry: &FsEntry) -> String {
+    let raw = if entry.is_directory {
+        entry.path.file_name()
+    } else {
+        entry.path.file_stem()
+    };
+    normalize_name(&raw.map(OsStr::to_string_lossy).unwrap_or_default())
+}
+
+fn modfile_key(m: &ModioMod) -> Option<String> {
+    m.modfile
+        .as_ref()
+        .and_then(|f| f.filename.as_deref())
+        .and_then(|f| Path::new(f).file_stem())
+        .map(|stem| normalize_name(&stem.to_string_lossy()))
+        .filter(|k| !k.is_empty())
+}
+
+/// Finds the most likely catalog entry for a local map, if any.
+pub fn match_local_map(
+    entry: &FsEntry,
+    catalog: &[ModioMod],
+    records: &[InstallRecord],
+) -> Option<ModioMatch> {
+    if let Some(record) = records.iter().find(|r| r.installed_path == entry.path) {
+        return Some(ModioMatch {
+            mod_id: record.mod_id,
+            confidence: 1.0,
+            method: MatchMethod::InstallRecord,
+        });
+    }
+    if let Some(mod_id) = entry.metadata.as_ref().and_then(|m| m.modio_id) {
+        return Some(ModioMatch {
+            mod_id,
+            confidence: 1.0,
+            method: MatchMethod::Metadata,
+        });
+    }
+
+    let key = entry_key(entry);
+    if key.is_empty() {
+        return None;
+    }
+
+    let mut best: Option<ModioMatch> = None;
+    for m in catalog {
+        let size_bonus = if sizes_match(entry.size, m) { 0.1 } else { 0.0 };
+
+        let candidate = if modfile_key(m).as_deref() == Some(key.as_str()) {
+            Some((0.9 + size_bonus / 2.0, MatchMethod::Filename))
+        } else {
+            let similarity = [normalize_name(&m.name), normalize_name(&m.name_id)]
+                .iter()
+                .map(|n| name_similarity(&key, n))
+                .fold(0.0, f64::max);
+            (similarity >= MIN_SIMILARITY)
+                .then(|| ((similarity * 0.85 + size_bonus).min(0.95), MatchMethod::NameSimilarity))
+        };
+
+        if let Some((confidence, method)) = candidate {
+            if !matches!(&best, Some(b) if b.confidence >= confidence) {
+                best = Some(ModioMatch {
+                    mod_id: m.id,
+                    confidence,
+                    method,
+                });
+            }
+        }
+    }
+    best
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::MapMetadata;
+    use crate::modio_catalog::ModioModfile;
+    use std::path::PathBuf;
+
+    fn entry(path: &str, is_directory: bool, size: Option<u64>) -> FsEntry {
+        FsEntry {
+            name: None,
+            path: PathBuf::from(path),
+            is_directory,
+            size,
+            modified: None,
+            thumbnail_path: None,
+            thumbnail_mime_type: None,
+            thumbnail_large_path: None,
+            modio_match: None,
+            metadata: None,
+            enabled: None,
+            library_root: None,
+            mod_info: None,
+            manifest_status: None,
+        }
+    }
+
+    fn catalog_mod(id: u64, name: &str, filename: Option<&str>, size: Option<u64>) -> ModioMod {
+        ModioMod {
+            id,
+            name: name.into(),
+            modfile: Some(ModioModfile {
+                filename: filename.map(String::from),
+                filesize_uncompressed: size,
+                ..Default::default()
+            }),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn normalizes_names() {
+        assert_eq!(normalize_name("Skate Park v2!"), "skatepark");
+        assert_eq!(normalize_name("The_Bowl-V3"), "thebowl");
+        assert_eq!(normalize_name("Park 2"), "park2");
+        assert_eq!(normalize_name("Vans Plaza"), "vansplaza");
+        assert_eq!(normalize_name("Über Spot"), "überspot");
+        assert_eq!(normalize_name("v10 - "), "");
+    }
+
+    #[test]
+    fn scores_bigram_similarity() {
+        assert_eq!(name_similarity("skatepark", "skatepark"), 1.0);
+        assert_eq!(name_similarity("", "skatepark"), 0.0);
+        assert_eq!(name_similarity("a", "b"), 0.0);
+        assert_eq!(name_similarity("night", "nacht"), 0.25);
+        assert_eq!(
+            name_similarity("berlin", "berlinplaza"),
+            name_similarity("berlinplaza", "berlin")
+        );
+        assert!(name_similarity("berlin", "berlinplaza") < MIN_SIMILARITY);
+        assert!(name_similarity("berlinplaza", "berlinplaza2") >= MIN_SIMILARITY);
+    }
+
+    #[test]
+    fn install_records_and_sidecars_win() {
+        let catalog = [catalog_mod(1, "Skate Park", None, None)];
+        let map = entry("/Maps/Skate Park", true, None);
+        let records = [InstallRecord {
+            mod_id: 7,
+            modfile_id: None,
+            filename: None,
+            installed_path: map.path.clone(),
+            installed_at: None,
+        }];
+        let found = match_local_map(&map, &catalog, &records).unwrap();
+        assert_eq!(
+            (found.mod_id, found.method),
+            (7, MatchMethod::InstallRecord)
+        );
+
+        let mut tagged = map.clone();
+        tagged.metadata = Some(MapMetadata {
+            modio_id: Some(9),
+            ..Default::default()
+        });
+        let found = match_local_map(&tagged, &catalog, &[]).unwrap();
+        assert_eq!((found.mod_id, found.method), (9, MatchMethod::Metadata));
+    }
+
+    #[test]
+    fn matches_by_filename_with_a_size_bonus() {
+        let catalog = [
+            catalog_mod(1, "Skatepark", None, None),
+            catalog_mod(2, "Something Else", Some("Skate_Park_v2.zip"), Some(1000)),
+        ];
+        let found = match_local_map(&entry("/Maps/Skate Park", true, None), &catalog, &[]).unwrap();
+        assert_eq!((found.mod_id, found.method), (2, MatchMethod::Filename));
+        assert_eq!(found.confidence, 0.9);
+
+        let sized = entry("/Maps/skate-park.zip", false, Some(1020));
+        let found = match_local_map(&sized, &catalog, &[]).unwrap();
+        assert_eq!(found.mod_id, 2);
+        assert!((found.confidence - 0.95).abs() < 1e-9);
+    }
+
+    #[test]
+    fn fuzzy_matches_need_enough_similarity() {
+        let catalog = [catalog_mod(1, "Berlin Plaza", None, None)];
+        let found =
+            match_local_map(&entry("/Maps/berlin_plaza", true, None), &catalog, &[]).unwrap();
+        assert_eq!(found.method, MatchMethod::NameSimilarity);
+        assert_eq!(found.confidence, 0.85);
+        assert!(match_local_map(&entry("/Maps/Berlin", true, None), &catalog, &[]).is_none());
+        assert!(match_local_map(&entry("/Maps/v2", true, None), &catalog, &[]).is_none());
+    }
+
+    #[test]
+    fn ties_keep_the_first_catalog_entry() {
+        let catalog = [
+            catalog_mod(1, "Warehouse", None, None),
+            catalog_mod(2, "Warehouse", None, None),
+        ];
+        let found = match_local_map(&entry("/Maps/Warehouse", true, None), &catalog, &[]).unwrap();
+        assert_eq!(found.mod_id, 1);
+    }
+}
@@ -0,0 +1,97 @@
+// src-tauri/src/map_metadata.rs
+
+//! Reads and writes `.xlfm.json` metadata sidecars for local maps.
+//! Folder maps keep the sidecar inside the folder; single-file maps get `<file>.xlfm.json` next to them.
+
+use crate::error::{CommandError, CommandResult};
+use crate::models::MapMetadata;
+use crate::modio_catalog::ModioMod;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+use tauri::command;
+
+pub const SIDECAR_NAME: &str = ".xlfm.json";
+
+/// Returns where the sidecar for a map lives.
+pub fn sidecar_path(map_path: &Path) -> PathBuf {
+    if map_path.is_dir() {
+        map_path.join(SIDECAR_NAME)
+    } else {
+        let mut name = map_path.file_name().unwrap_or_default().to_os_string();
+        name.push(SIDECAR_NAME);
+        map_path.with_file_name(name)
+    }
+}
+
+/// Reads a map's sidecar. Missing or unparsable sidecars yield `None`.
+pub fn read_sidecar(map_path: &Path) -> Option<MapMetadata> {
+    let path = sidecar_path(map_path);
+    let bytes = fs::read(&path).ok()?;
+    serde_json::from_slice(&bytes)
+        .map_err(|e| {
+            log::warn!(
+                "[map_metadata::read] Ignoring malformed sidecar {}: {}",
+                path.display(),
+                e
+            )
+        })
+        .ok()
+}
+
+pub fn write_sidecar(map_path: &Path, metadata: &MapMetadata) -> CommandResult<()> {
+    let path = sidecar_path(map_path);
+    let json = serde_json::to_vec_pretty(metadata)
+        .map_err(|e| CommandError::Input(format!("Failed to serialize metadata: {}", e)))?;
+    fs::write(&path, json)
+        .map_err(|e| CommandError::Io(format!("Failed to write {}: {}", path.display(), e)))
+}
+
+/// Builds sidecar metadata from a catalog entry, keeping any user rating already present.
+pub fn metadata_from_modio(
+    m: &ModioMod,
+    installed_at: Option<u64>,
+    existing: Option<MapMetadata>,
+) -> MapMetadata {
+    MapMetadata {
+        author: m.submitted_by.as_ref().map(|u| u.username.clone()),
+        modio_id: Some(m.id),
+        version: m.modfile.as_ref().and_then(|f| f.version.clone()),
+        description: Some(m.summary.clone()).filter(|s| !s.is_empty()),
+        tags: m.tags.iter().map(|t| t.name.clone()).collect(),
+        installed_at: installed_at.or(existing.as_ref().and_then(|e| e.installed_at)),
+        user_rating: existing.and_then(|e| e.user_rating),
+    }
+}
+
+#[command]
+pub fn get_map_metadata(map_path: String) -> CommandResult<Option<MapMetadata>> {
+    Ok(read_sidecar(Path::new(&map_path)))
+}
+
+#[command]
+pub fn set_map_metadata(map_path: String, metadata: MapMetadata) -> CommandResult<()> {
+    let path = Path::new(&map_path);
+    if !path.exists() {
+        return Err(CommandError::Input(format!("Map not found: {}", map_path)));
+    }
+    if metadata.user_rating.is_some_and(|r| !(1..=5).contains(&r)) {
+        return Err(CommandError::Input("Rating must be between 1 and 5".into()));
+    }
+    write_sidecar(path, &metadata)
+}
+
+#[command]
+pub fn set_map_rating(map_path: String, rating: Option<u8>) -> CommandResult<()> {
+    let path = Path::new(&map_path);
+    if !path.exists() {
+        return Err(CommandError::Input(format!("Map not found: {}", map_path)));
+    }
+    if rating.is_some_and(|r| !(1..=5).contains(&r)) {
+        return Err(CommandError::Input("Rating must be between 1 and 5".into()));
+    }
+    let mut metadata = read_sidecar(path).unwrap_or_default();
+    metadata.user_rating = rating;
+    write_sidecar(path, &metadata)
+}
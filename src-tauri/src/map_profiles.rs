@@ -0,0 +1,400 @@
+// src-tauri/src/map_profiles.rs
+
+//! Named map profiles (loadouts). Every profile references maps in a central library;
+//! activating one builds a folder of links into that library and points the game's Maps link at it.
+
+use crate::content_folders::{restore_from_backup, ContentFolder};
+use crate::error::{CommandError, CommandResult};
+use crate::link_backend::{LinkBackend, PlatformLinkBackend};
+use crate::map_commands::create_maps_symlink;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+use tauri::{command, AppHandle, Manager};
+
+const PROFILES_FILE: &str = "map_profiles.json";
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct MapProfile {
+    pub name: String,
+    /// Entry names (folder or file names) inside the map library.
+    pub maps: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct MapProfilesConfig {
+    #[serde(default)]
+    pub library_path: Option<PathBuf>,
+    #[serde(default)]
+    pub profiles: Vec<MapProfile>,
+    #[serde(default)]
+    pub active_profile: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileActivationResult {
+    pub profile_path: PathBuf,
+    pub linked: usize,
+    /// Maps referenced by the profile that are no longer in the library.
+    pub missing: Vec<String>,
+}
+
+fn profiles_file(app_handle: &AppHandle) -> CommandResult<PathBuf> {
+    app_handle
+        .path()
+        .app_data_dir()
+        .map(|dir| dir.join(PROFILES_FILE))
+        .map_err(|e| CommandError::DirectoryResolution(e.to_string()))
+}
+
+pub fn load_profiles(app_handle: &AppHandle) -> CommandResult<MapProfilesConfig> {
+    let path = profiles_file(app_handle)?;
+    match fs::read(&path) {
+        Ok(bytes) => serde_json::from_slice(&bytes).map_err(|e| {
+            CommandError::Input(format!("Corrupt profiles file {}: {}", path.display(), e))
+        }),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(MapProfilesConfig::default()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+fn save_profiles(app_handle: &AppHandle, config: &MapProfilesConfig) -> CommandResult<()> {
+    let path = profiles_file(app_handle)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let json = serde_json::to_vec_pretty(config)
+        .map_err(|e| CommandError::Input(format!("Failed to serialize profiles: {}", e)))?;
+    fs::write(&path, json)?;
+    Ok(())
+}
+
+/// Turns a profile name into a safe folder name.
+fn profile_dir_name(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '-' || c == '_' || c == ' ' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect::<String>()
+        .trim()
+        .to_string()
+}
+
+/// Another profile whose name maps to the same folder as `name`. Folder names are compared
+/// case-insensitively, as on Windows.
+fn colliding_profile<'a>(profiles: &'a [MapProfile], name: &str) -> Option<&'a MapProfile> {
+    let dir = profile_dir_name(name).to_lowercase();
+    profiles
+        .iter()
+        .find(|p| p.name != name && profile_dir_name(&p.name).to_lowercase() == dir)
+}
+
+/// The path of `link` itself with its parent resolved, without following `link`.
+fn link_location(link: &Path) -> CommandResult<PathBuf> {
+    let (Some(parent), Some(name)) = (link.parent(), link.file_name()) else {
+        return Err(CommandError::Input(format!(
+            "Invalid target path: {}",
+            link.display()
+        )));
+    };
+    Ok(fs::canonicalize(parent)?.join(name))
+}
+
+/// Whether either path contains the other.
+fn overlaps(a: &Path, b: &Path) -> bool {
+    a.starts_with(b) || b.starts_with(a)
+}
+
+/// Profile folders live next to the library so file hardlinks stay on one volume.
+fn profiles_root(library: &Path) -> CommandResult<PathBuf> {
+    let name = library.file_name().ok_or_else(|| {
+        CommandError::Input(format!("Invalid library path: {}", library.display()))
+    })?;
+    Ok(library.with_file_name(format!("{}_profiles", name.to_string_lossy())))
+}
+
+/// Whether `name` is a single file or folder name, with no separators or `..`.
+pub(crate) fn is_plain_entry_name(name: &str) -> bool {
+    let mut parts = Path::new(name).components();
+    matches!((parts.next(), parts.next()), (Some(std::path::Component::Normal(_)), None))
+}
+
+/// Links one library entry into a profile folder: directories get a symlink/junction,
+/// files a hardlink, or a symlink where hardlinks fail (such as across drives).
+pub(crate) fn link_map_entry(
+    backend: &impl LinkBackend,
+    source: &Path,
+    dest: &Path,
+) -> CommandResult<()> {
+    if source.is_dir() {
+        return Ok(backend.create_dir_link(source, dest)?);
+    }
+    if let Err(e) = fs::hard_link(source, dest) {
+        log::info!(
+            "[map_profiles::link_map_entry] Hardlink failed for {} ({}), using a symlink",
+            source.display(),
+            e
+        );
+        backend.create_file_link(source, dest)?;
+    }
+    Ok(())
+}
+
+#[command]
+pub fn list_map_profiles(app_handle: AppHandle) -> CommandResult<MapProfilesConfig> {
+    load_profiles(&app_handle)
+}
+
+#[command]
+pub fn set_map_library(app_handle: AppHandle, library_path: String) -> CommandResult<()> {
+    let library = PathBuf::from(&library_path);
+    if !library.is_dir() {
+        return Err(CommandError::Input(format!(
+            "Library is not a directory: {}",
+            library_path
+        )));
+    }
+    let mut config = load_profiles(&app_handle)?;
+    config.library_path = Some(library);
+    save_profiles(&app_handle, &config)
+}
+
+/// Creates a profile, or replaces the map list of an existing one.
+#[command]
+pub fn save_map_profile(
+    app_handle: AppHandle,
+    name: String,
+    maps: Vec<String>,
+) -> CommandResult<()> {
+    if profile_dir_name(&name).is_empty() {
+        return Err(CommandError::Input("Profile name cannot be empty".into()));
+    }
+    if let Some(bad) = maps.iter().find(|m| !is_plain_entry_name(m)) {
+        return Err(CommandError::Input(format!(
+            "Map '{}' must be a plain entry name inside the library",
+            bad
+        )));
+    }
+    let mut config = load_profiles(&app_handle)?;
+    if let Some(other) = colliding_profile(&config.profiles, &name) {
+        return Err(CommandError::Input(format!(
+            "Profile '{}' would share a folder with '{}'. Choose a different name.",
+            name, other.name
+        )));
+    }
+    match config.profiles.iter_mut().find(|p| p.name == name) {
+        Some(existing) => existing.maps = maps,
+        None => config.profiles.push(MapProfile { name, maps }),
+    }
+    save_profiles(&app_handle, &config)
+}
+
+#[command]
+pub fn delete_map_profile(app_handle: AppHandle, name: String) -> CommandResult<()> {
+    let mut config = load_profiles(&app_handle)?;
+    if config.active_profile.as_deref() == Some(name.as_str()) {
+        return Err(CommandError::Input(format!(
+            "Profile '{}' is active. Deactivate it first.",
+            name
+        )));
+    }
+    config.profiles.retain(|p| p.name != name);
+    save_profiles(&app_handle, &config)?;
+
+    if let Some(library) = config.library_path {
+        let dir = profiles_root(&library)?.join(profile_dir_name(&name));
+        if dir.is_dir() {
+            fs::remove_dir_all(&dir)?;
+        }
+    }
+    Ok(())
+}
+
+/// Rebuilds the profile's link folder from the library and points `target_link` at it.
+#[command]
+pub fn activate_map_profile(
+    app_handle: AppHandle,
+    name: String,
+    target_link: String,
+) -> CommandResult<ProfileActivationResult> {
+    let mut config = load_profiles(&app_handle)?;
+    let library = config
+        .library_path
+        .clone()
+        .filter(|p| p.is_dir())
+        .ok_or_else(|| CommandError::Input("No map library configured".into()))?;
+    let profile = config
+        .profiles
+        .iter()
+        .find(|p| p.name == name)
+        .cloned()
+        .ok_or_else(|| CommandError::Input(format!("Unknown profile: {}", name)))?;
+
+    // Linking over a folder that holds the library would move the library into the backup.
+    let real_library = fs::canonicalize(&library)?;
+    let target = link_location(Path::new(&target_link))?;
+    if overlaps(&real_library, &target) {
+        return Err(CommandError::Input(format!(
+            "The map library {} overlaps the Maps folder {}; move one of them first",
+            real_library.display(),
+            target.display()
+        )));
+    }
+
+    let profile_dir = profiles_root(&library)?.join(profile_dir_name(&name));
+    if profile_dir.exists() {
+        // Only links live here, and remove_dir_all does not follow them.
+        fs::remove_dir_all(&profile_dir)?;
+    }
+    fs::create_dir_all(&profile_dir)?;
+
+    let mut linked = 0;
+    let mut missing = Vec::new();
+    for map in &profile.maps {
+        let source = library.join(map);
+        if !source.exists() {
+            log::warn!("[map_profiles::activate] '{}' missing from library", map);
+            missing.push(map.clone());
+            continue;
+        }
+        link_map_entry(&PlatformLinkBackend, &source, &profile_dir.join(map))?;
+        linked += 1;
+    }
+
+    create_maps_symlink(profile_dir.to_string_lossy().into_owned(), target_link)?;
+
+    config.active_profile = Some(name.clone());
+    save_profiles(&app_handle, &config)?;
+    log::info!(
+        "[map_profiles::activate] '{}' active with {} maps ({} missing)",
+        name,
+        linked,
+        missing.len()
+    );
+    Ok(ProfileActivationResult {
+        profile_path: profile_dir,
+        linked,
+        missing,
+    })
+}
+
+/// Swaps the profile link at `link` for the Maps folder activation moved aside.
+/// Refuses links that do not point at `profile_dir`.
+fn restore_maps_folder(
+    backend: &impl LinkBackend,
+    link: &Path,
+    profile_dir: &Path,
+) -> CommandResult<()> {
+    let target = if backend.is_link(link)? {
+        let t = backend.read_link(link)?;
+        match link.parent() {
+            Some(parent) if t.is_relative() => Some(parent.join(t)),
+            _ => Some(t),
+        }
+    } else {
+        None
+    };
+    let points_at_profile = target
+        .and_then(|t| fs::canonicalize(t).ok())
+        .is_some_and(|t| fs::canonicalize(profile_dir).is_ok_and(|p| p == t));
+    if !points_at_profile {
+        return Err(CommandError::Input(format!(
+            "{} is not linked to the profile folder {}",
+            link.display(),
+            profile_dir.display()
+        )));
+    }
+    restore_from_backup(backend, ContentFolder::Maps, link)?;
+    Ok(())
+}
+
+/// Removes the Maps link created by the active profile, puts the original Maps folder
+/// back and clears the active profile.
+#[command]
+pub fn deactivate_map_profile(app_handle: AppHandle, target_link: String) -> CommandResult<()> {
+    let mut config = load_profiles(&app_handle)?;
+    let (Some(library), Some(active)) = (&config.library_path, &config.active_profile) else {
+        return Err(CommandError::Input("No map profile is active".into()));
+    };
+    let profile_dir = profiles_root(library)?.join(profile_dir_name(active));
+    restore_maps_folder(&PlatformLinkBackend, Path::new(&target_link), &profile_dir)?;
+    log::info!("[map_profiles::deactivate] '{}' deactivated", active);
+    config.active_profile = None;
+    save_profiles(&app_handle, &config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile(name: &str) -> MapProfile {
+        MapProfile {
+            name: name.into(),
+            maps: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn detects_names_sharing_a_folder() {
+        let profiles = [profile("Street/Park"), profile("Skatepark")];
+        assert_eq!(
+            colliding_profile(&profiles, "Street?Park").map(|p| p.name.as_str()),
+            Some("Street/Park")
+        );
+        assert!(colliding_profile(&profiles, "skatepark ").is_some());
+        assert!(colliding_profile(&profiles, "Street/Park").is_none());
+        assert!(colliding_profile(&profiles, "Plaza").is_none());
+    }
+
+    #[test]
+    fn library_and_target_overlap_in_either_direction() {
+        let maps = Path::new("/games/SkaterXL/Maps");
+        assert!(overlaps(maps, maps));
+        assert!(overlaps(Path::new("/games/SkaterXL/Maps/Library"), maps));
+        assert!(overlaps(Path::new("/games"), maps));
+        assert!(!overlaps(Path::new("/games/SkaterXL/MapLibrary"), maps));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn deactivating_restores_the_original_maps() {
+        use crate::link_backend::link_folder_with_backup;
+
+        let root = std::env::temp_dir().join(format!("xlfm-profiles-{}", uuid::Uuid::new_v4()));
+        let (maps, profile_dir) = (
+            root.join("Maps"),
+            root.join("Library_profiles").join("Park"),
+        );
+        fs::create_dir_all(maps.join("Original")).unwrap();
+        fs::create_dir_all(&profile_dir).unwrap();
+        let other = root.join("Library_profiles").join("Other");
+        fs::create_dir_all(&other).unwrap();
+
+        // Activation links Maps to the profile folder and moves the real folder aside.
+        link_folder_with_backup(&PlatformLinkBackend, &profile_dir, &maps).unwrap();
+        assert!(PlatformLinkBackend.is_link(&maps).unwrap());
+
+        assert!(matches!(
+            restore_maps_folder(&PlatformLinkBackend, &maps, &other),
+            Err(CommandError::Input(_))
+        ));
+        restore_maps_folder(&PlatformLinkBackend, &maps, &profile_dir).unwrap();
+        assert!(!PlatformLinkBackend.is_link(&maps).unwrap());
+        assert!(maps.join("Original").is_dir());
+        assert!(profile_dir.is_dir());
+        assert!(!root.join("Maps_backup").exists());
+
+        // A real Maps folder is not a profile link.
+        assert!(restore_maps_folder(&PlatformLinkBackend, &maps, &profile_dir).is_err());
+        fs::remove_dir_all(root).unwrap();
+    }
+}
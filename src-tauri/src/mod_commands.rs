@@ -2,183 +2,62 @@
 
 //! Contains Tauri commands specifically for mod‑related operations.
 
+use crate::content_folders::ContentFolder;
+use crate::content_scanner::{check_listing_root, sort_by_name, ContentScanner};
 use crate::error::{CommandError, CommandResult};
+use crate::map_commands::{disabled_store_for, DISABLED_SUFFIX};
 use crate::models::*;
-use crate::utils::*;
+use crate::settings;
+use crate::umm::{self, read_mod_info};
+use serde::{Deserialize, Serialize};
 use std::{
-    collections::{hash_map::DefaultHasher, HashMap, HashSet},
     ffi::OsStr,
     fs,
-    hash::{Hash, Hasher},
     path::{Path, PathBuf},
 };
-use tauri::{command, Manager};
-
-fn hash_path(path: &Path) -> String {
-    let path_str = path.to_string_lossy();
-    let mut hasher = DefaultHasher::new();
-    path_str.hash(&mut hasher);
-    format!("{:x}", hasher.finish())
-}
-
-fn cache_thumbnail_if_needed(
-    original_thumbnail_path: &Path,
-    original_mod_path: &Path,
-    app_handle: &tauri::AppHandle,
-) -> Option<PathBuf> {
-    let base_cache_dir = app_handle.path().app_cache_dir().ok()?;
-    let cache_dir = base_cache_dir.join("thumbnails").join("mods");
-    if !cache_dir.exists() {
-        fs::create_dir_all(&cache_dir).ok()?;
-    }
-
-    let ext = original_thumbnail_path.extension().and_then(|e| e.to_str()).unwrap_or_default();
-    let cache_filename = format!("{}.{}", hash_path(original_mod_path), ext);
-    let cached_thumb_path = cache_dir.join(&cache_filename);
-
-    if !cached_thumb_path.exists() {
-        fs::copy(original_thumbnail_path, &cached_thumb_path).ok()?;
-    }
-
-    Some(cached_thumb_path)
-}
+use tauri::command;
 
 #[command]
 pub fn list_local_mods(
     app_handle: tauri::AppHandle,
-    relative_maps_path: String,
+    folder: Option<String>,
 ) -> CommandResult<DirectoryListingResult> {
-    // identical to list_local_maps but uses "mods" cache path
     let mods_folder_path =
-        resolve_document_path(&relative_maps_path).map_err(CommandError::DirectoryResolution)?;
-
-    if !mods_folder_path.exists() {
+        settings::resolve_content_root(&app_handle, folder, ContentFolder::Mods)?;
+    if !check_listing_root(&mods_folder_path)? {
         return Ok(DirectoryListingResult {
             status: ListingStatus::DoesNotExist,
             entries: Vec::new(),
             path: mods_folder_path,
         });
     }
-    if !mods_folder_path.is_dir() {
-        return Err(CommandError::Input(format!(
-            "Path exists but is not a directory: {}",
-            mods_folder_path.display()
-        )));
-    }
 
-    let mut mod_entries = Vec::new();
-    let mut thumbnail_map: HashMap<String, (PathBuf, String)> = HashMap::new();
-    let mut is_empty = true;
-
-    let mut dir_reader_peek = match fs::read_dir(&mods_folder_path) {
-        Ok(reader) => reader.peekable(),
-        Err(e) => {
-            return Err(CommandError::Io(format!(
-                "Failed read dir (peek) {}: {}",
-                mods_folder_path.display(),
-                e
-            )))
-        }
-    };
-
-    if dir_reader_peek.peek().is_some() {
-        is_empty = false;
-        log::info!("[mod_commands::list_local_mods] Directory not empty. Scanning for thumbnails...");
-
-        let dir_reader_thumbs = fs::read_dir(&mods_folder_path).map_err(|e| {
-            CommandError::Io(format!(
-                "Failed read dir (thumbs) {}: {}",
-                mods_folder_path.display(),
-                e
-            ))
-        })?;
-
-        for entry_result in dir_reader_thumbs {
-            if let Ok(entry) = entry_result {
-                let path = entry.path();
-                let file_name_os = entry.file_name();
-                let file_name_str = file_name_os.to_string_lossy();
-
-                if path.is_dir() {
-                    let dir_name_lower = file_name_str.to_lowercase();
-                    for ext in THUMBNAIL_EXTS.iter() {
-                        let candidate = path.join(format!("{}.{}", file_name_str, ext));
-                        if candidate.is_file() {
-                            if let Some(mime) = get_mime_type_from_extension(ext) {
-                                thumbnail_map.insert(dir_name_lower.clone(), (candidate, mime));
-                                break;
-                            }
-                        }
-                    }
-                }
-            }
-        }
+    let mut scanner = ContentScanner::for_app(&app_handle, ContentFolder::Mods);
+    let (is_empty, mut mod_entries) = scanner.scan_folder(&mods_folder_path)?;
+
+    // UMM's Params.xml decides for mods in the Mods folder; unknown mods load by default.
+    let states = umm::game_install_dir(&app_handle)
+        .and_then(|dir| umm::params_path(&dir))
+        .and_then(|path| fs::read_to_string(path).ok())
+        .map(|xml| umm::read_mod_states(&xml))
+        .unwrap_or_default();
+    for entry in mod_entries.iter_mut() {
+        let id = entry.mod_info.as_ref().map(|info| &info.id);
+        entry.enabled = Some(id.and_then(|id| states.get(id)).copied().unwrap_or(true));
     }
 
-    let mut valid_cached_filenames: HashSet<String> = HashSet::new();
-
-    if !is_empty {
-        let dir_reader_entries = fs::read_dir(&mods_folder_path).map_err(|e| {
-            CommandError::Io(format!("Failed read dir (entries) {}: {}", mods_folder_path.display(), e))
-        })?;
-
-        for entry_result in dir_reader_entries {
-            if let Ok(entry) = entry_result {
-                let path = entry.path();
-                let metadata = match entry.metadata() {
-                    Ok(m) => m,
-                    Err(e) => {
-                        log::error!("[mod_commands::list_local_mods] Metadata error for {}: {}", path.display(), e);
-                        continue;
-                    }
-                };
-                let is_directory = metadata.is_dir();
-                let name = entry.file_name().to_str().map(|s| s.to_string());
-
-                let size = if is_directory {
-                    Some(calculate_directory_size(&path))
-                } else {
-                    Some(metadata.len())
-                };
-
-                let modified_time = system_time_to_millis(metadata.modified().ok())
-                    .or_else(|| system_time_to_millis(metadata.created().ok()));
-
-                let key = if is_directory {
-                    name.clone().map(|n| n.to_lowercase())
-                } else {
-                    path.file_stem().and_then(OsStr::to_str).map(str::to_lowercase)
-                };
-
-                let mut final_thumb_path: Option<PathBuf> = None;
-                let mut final_thumb_mime: Option<String> = None;
-
-                if let Some((thumb_path, thumb_mime)) = key.and_then(|k| thumbnail_map.get(&k)) {
-                    if let Some(cached_path) = cache_thumbnail_if_needed(thumb_path, &path, &app_handle) {
-                        if let Some(filename_osstr) = cached_path.file_name() {
-                            valid_cached_filenames.insert(filename_osstr.to_string_lossy().to_string());
-                        }
-                        final_thumb_path = Some(cached_path);
-                        final_thumb_mime = Some(thumb_mime.clone());
-                    }
-                }
-
-                mod_entries.push(FsEntry {
-                    name,
-                    path,
-                    is_directory,
-                    size,
-                    modified: modified_time,
-                    thumbnail_path: final_thumb_path,
-                    thumbnail_mime_type: final_thumb_mime,
-                });
-            }
-        }
+    if let Some(store) = disabled_store_for(&mods_folder_path).filter(|p| p.is_dir()) {
+        let (_, disabled_entries) = scanner.scan_folder(&store)?;
+        mod_entries.extend(disabled_entries.into_iter().map(|mut entry| {
+            entry.enabled = Some(false);
+            entry
+        }));
     }
 
-    mod_entries.sort_by(|a, b| a.name.as_deref().unwrap_or("").cmp(b.name.as_deref().unwrap_or("")));
+    sort_by_name(&mut mod_entries);
+    scanner.finish();
 
-    let final_status = if is_empty {
+    let final_status = if is_empty && mod_entries.is_empty() {
         ListingStatus::ExistsAndEmpty
     } else {
         ListingStatus::ExistsAndPopulated
@@ -192,3 +71,155 @@ pub fn list_local_mods(
         path: mods_folder_path,
     })
 }
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum ToggleMethod {
+    /// The mod's `Enabled` flag in Unity Mod Manager's Params.xml.
+    Params,
+    /// The mod folder was moved to or from `Mods_disabled`.
+    DisabledStore,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ModToggleResult {
+    /// Where the mod folder is now.
+    pub path: PathBuf,
+    pub enabled: bool,
+    pub method: ToggleMethod,
+}
+
+/// The Mods folder a disabled store belongs to, if `folder` is one.
+fn store_owner(folder: &Path) -> Option<PathBuf> {
+    folder
+        .file_name()
+        .and_then(OsStr::to_str)
+        .and_then(|name| name.strip_suffix(DISABLED_SUFFIX))
+        .map(|name| folder.with_file_name(name))
+}
+
+fn move_mod(path: &Path, dest_dir: &Path) -> CommandResult<PathBuf> {
+    let name = path
+        .file_name()
+        .ok_or_else(|| CommandError::Input(format!("Invalid mod path: {}", path.display())))?;
+    let dest = dest_dir.join(name);
+    if fs::symlink_metadata(&dest).is_ok() {
+        return Err(CommandError::Input(format!(
+            "A mod named {} already exists in {}",
+            name.to_string_lossy(),
+            dest_dir.display()
+        )));
+    }
+    fs::create_dir_all(dest_dir)?;
+    fs::rename(path, &dest)?;
+    Ok(dest)
+}
+
+/// Sets the mod's flag in Params.xml. Returns false when UMM can't be used for this mod
+/// (no Params.xml found, or no valid `Info.json` Id).
+fn set_params_state(
+    app_handle: &tauri::AppHandle,
+    mod_dir: &Path,
+    enabled: bool,
+) -> CommandResult<bool> {
+    let (Some(info), _) = read_mod_info(mod_dir) else {
+        return Ok(false);
+    };
+    let Some(params) = umm::game_install_dir(app_handle).and_then(|dir| umm::params_path(&dir))
+    else {
+        return Ok(false);
+    };
+    let xml = fs::read_to_string(&params)?;
+    let updated = umm::set_mod_state(&xml, &info.id, enabled)
+        .map_err(|e| CommandError::Input(format!("{}: {}", params.display(), e)))?;
+    if updated != xml {
+        umm::write_params(&params, &updated)?;
+    }
+    Ok(true)
+}
+
+fn toggle_mod(
+    app_handle: &tauri::AppHandle,
+    mod_path: &str,
+    enabled: bool,
+) -> CommandResult<ModToggleResult> {
+    let path = Path::new(mod_path);
+    if !path.is_dir() {
+        return Err(CommandError::Input(format!("Mod folder not found: {}", mod_path)));
+    }
+    let parent = path
+        .parent()
+        .ok_or_else(|| CommandError::Input(format!("Invalid mod path: {}", mod_path)))?;
+
+    let result = match store_owner(parent) {
+        // Disabled by moving: enabling moves it back, and clears any stale UMM flag too.
+        Some(mods_folder) if enabled => {
+            let dest = move_mod(path, &mods_folder)?;
+            // The move already happened, so a leftover flag is logged rather than failing the toggle.
+            if let Err(e) = set_params_state(app_handle, &dest, true) {
+                log::warn!(
+                    "[mod_commands::enable_mod] Could not clear the UMM flag for {}: {}",
+                    dest.display(),
+                    e
+                );
+            }
+            log::info!("[mod_commands::enable_mod] {} -> {}", path.display(), dest.display());
+            ModToggleResult {
+                path: dest,
+                enabled,
+                method: ToggleMethod::DisabledStore,
+            }
+        }
+        Some(_) => {
+            return Err(CommandError::Input(format!("Mod is already disabled: {}", mod_path)))
+        }
+        None if set_params_state(app_handle, path, enabled)? => {
+            log::info!(
+                "[mod_commands::toggle_mod] {} enabled={} via Params.xml",
+                path.display(),
+                enabled
+            );
+            ModToggleResult {
+                path: path.to_path_buf(),
+                enabled,
+                method: ToggleMethod::Params,
+            }
+        }
+        None if enabled => ModToggleResult {
+            path: path.to_path_buf(),
+            enabled,
+            method: ToggleMethod::DisabledStore,
+        },
+        None => {
+            let store = disabled_store_for(parent).ok_or_else(|| {
+                CommandError::DirectoryResolution(format!(
+                    "Could not resolve disabled store for {}",
+                    parent.display()
+                ))
+            })?;
+            let dest = move_mod(path, &store)?;
+            log::info!("[mod_commands::disable_mod] {} -> {}", path.display(), dest.display());
+            ModToggleResult {
+                path: dest,
+                enabled,
+                method: ToggleMethod::DisabledStore,
+            }
+        }
+    };
+    Ok(result)
+}
+
+/// Enables a mod: clears its UMM flag, or moves it back out of `Mods_disabled`.
+#[command]
+pub fn enable_mod(app_handle: tauri::AppHandle, mod_path: String) -> CommandResult<ModToggleResult> {
+    toggle_mod(&app_handle, &mod_path, true)
+}
+
+/// Disables a mod through UMM's Params.xml when possible, so UMM still lists it; mods
+/// without an Id or installs without UMM fall back to moving the folder to `Mods_disabled`.
+/// UMM rewrites Params.xml on exit, so toggling while the game runs may not stick.
+#[command]
+pub fn disable_mod(app_handle: tauri::AppHandle, mod_path: String) -> CommandResult<ModToggleResult> {
+    toggle_mod(&app_handle, &mod_path, false)
+}
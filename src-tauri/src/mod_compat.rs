@@ -0,0 +1,405 @@
+// src-tauri/src/mod_compat.rs
+
+//! Checks the Mods folder as a whole: missing or outdated requirements, mods built for a
+//! newer game or Unity Mod Manager, and the same mod installed twice.
+
+use crate::content_folders::ContentFolder;
+use crate::error::CommandResult;
+use crate::mod_loaders::{detect_loader, load_loader_records, LoaderKind};
+use crate::models::{ManifestStatus, ModInfo};
+use crate::path_finder::installation_at;
+use crate::settings;
+use crate::umm::{game_install_dir, read_mod_info};
+use serde::{Deserialize, Serialize};
+use std::{cmp::Ordering, collections::HashMap, fs, path::PathBuf};
+use tauri::{command, AppHandle};
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum IssueSeverity {
+    /// The mod will fail to load or crash the game.
+    Error,
+    /// The mod may misbehave.
+    Warning,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum CompatIssueKind {
+    MissingManifest,
+    MalformedManifest {
+        message: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    MissingRequirement {
+        required_id: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    OutdatedRequirement {
+        required_id: String,
+        required_version: String,
+        installed_version: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    GameVersion {
+        required: String,
+        detected: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    ManagerVersion {
+        required: String,
+        installed: String,
+    },
+    DuplicateId {
+        folders: Vec<PathBuf>,
+    },
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CompatIssue {
+    pub severity: IssueSeverity,
+    /// Folder of the mod the issue is about.
+    pub folder: PathBuf,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mod_id: Option<String>,
+    #[serde(flatten)]
+    pub kind: CompatIssueKind,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CompatibilityReport {
+    pub mods_path: PathBuf,
+    pub mods_checked: usize,
+    /// From settings; `GameVersion` checks are skipped when unknown.
+    pub game_version: Option<String>,
+    pub game_build_id: Option<u64>,
+    /// `ManagerVersion` checks are skipped when unknown.
+    pub manager_version: Option<String>,
+    pub issues: Vec<CompatIssue>,
+}
+
+/// A mod folder as seen by the checker.
+pub struct ScannedMod {
+    pub folder: PathBuf,
+    pub info: Option<ModInfo>,
+    pub status: ManifestStatus,
+}
+
+/// Compares dotted versions numerically; missing parts count as zero, so `1.2` == `1.2.0`.
+/// Non-numeric suffixes such as `-beta` are ignored.
+pub fn compare_versions(a: &str, b: &str) -> Ordering {
+    let parse = |v: &str| -> Vec<u64> {
+        v.trim()
+            .trim_start_matches(['v', 'V'])
+            .split('.')
+            .map(|part| {
+                part.chars()
+                    .take_while(|c| c.is_ascii_digit())
+                    .collect::<String>()
+                    .parse()
+                    .unwrap_or(0)
+            })
+            .collect()
+    };
+    let (a, b) = (parse(a), parse(b));
+    (0..a.len().max(b.len()))
+        .map(|i| a.get(i).unwrap_or(&0).cmp(b.get(i).unwrap_or(&0)))
+        .find(|o| o.is_ne())
+        .unwrap_or(Ordering::Equal)
+}
+
+/// Splits a UMM requirement into Id and minimum version: `"Mod-1.2.0"` or plain `"Mod"`.
+/// Ids may contain dashes (`XL-2DTricks`), so only a trailing dotted version such as `1.2`
+/// counts as a version.
+pub fn parse_requirement(requirement: &str) -> (&str, Option<&str>) {
+    let is_version = |v: &str| {
+        v.contains('.')
+            && v.split('.')
+                .all(|part| !part.is_empty() && part.bytes().all(|b| b.is_ascii_digit()))
+    };
+    match requirement.rsplit_once('-') {
+        Some((id, version)) if !id.is_empty() && is_version(version) => (id, Some(version)),
+        _ => (requirement, None),
+    }
+}
+
+pub fn check_mods(
+    mods: &[ScannedMod],
+    game_version: Option<&str>,
+    manager_version: Option<&str>,
+) -> Vec<CompatIssue> {
+    let mut issues = Vec::new();
+    let mut by_id: HashMap<String, Vec<&ScannedMod>> = HashMap::new();
+    for m in mods {
+        if let Some(info) = &m.info {
+            by_id.entry(info.id.to_lowercase()).or_default().push(m);
+        }
+    }
+
+    for m in mods {
+        let info = match (&m.info, &m.status) {
+            (Some(info), _) => info,
+            (None, ManifestStatus::Malformed { message }) => {
+                issues.push(CompatIssue {
+                    severity: IssueSeverity::Error,
+                    folder: m.folder.clone(),
+                    mod_id: None,
+                    kind: CompatIssueKind::MalformedManifest {
+                        message: message.clone(),
+                    },
+                });
+                continue;
+            }
+            (None, _) => {
+                issues.push(CompatIssue {
+                    severity: IssueSeverity::Error,
+                    folder: m.folder.clone(),
+                    mod_id: None,
+                    kind: CompatIssueKind::MissingManifest,
+                });
+                continue;
+            }
+        };
+        let issue = |severity, kind| CompatIssue {
+            severity,
+            folder: m.folder.clone(),
+            mod_id: Some(info.id.clone()),
+            kind,
+        };
+
+        for requirement in &info.requirements {
+            let (required_id, required_version) = parse_requirement(requirement);
+            let installed = by_id
+                .get(&required_id.to_lowercase())
+                .and_then(|found| found.first())
+                .and_then(|found| found.info.as_ref());
+            match (installed, required_version) {
+                (None, _) => issues.push(issue(
+                    IssueSeverity::Error,
+                    CompatIssueKind::MissingRequirement {
+                        required_id: required_id.to_string(),
+                    },
+                )),
+                (Some(dep), Some(required)) => {
+                    let installed_version = dep.version.as_deref().unwrap_or("0");
+                    if compare_versions(installed_version, required).is_lt() {
+                        issues.push(issue(
+                            IssueSeverity::Error,
+                            CompatIssueKind::OutdatedRequirement {
+                                required_id: required_id.to_string(),
+                                required_version: required.to_string(),
+                                installed_version: installed_version.to_string(),
+                            },
+                        ));
+                    }
+                }
+                (Some(_), None) => {}
+            }
+        }
+
+        if let (Some(required), Some(detected)) = (info.game_version.as_deref(), game_version) {
+            if compare_versions(detected, required).is_lt() {
+                issues.push(issue(
+                    IssueSeverity::Warning,
+                    CompatIssueKind::GameVersion {
+                        required: required.to_string(),
+                        detected: detected.to_string(),
+                    },
+                ));
+            }
+        }
+        if let (Some(required), Some(installed)) =
+            (info.manager_version.as_deref(), manager_version)
+        {
+            if compare_versions(installed, required).is_lt() {
+                issues.push(issue(
+                    IssueSeverity::Error,
+                    CompatIssueKind::ManagerVersion {
+                        required: required.to_string(),
+                        installed: installed.to_string(),
+                    },
+                ));
+            }
+        }
+    }
+
+    for m in mods {
+        let Some(info) = &m.info else { continue };
+        let same = &by_id[&info.id.to_lowercase()];
+        if same.len() > 1 {
+            issues.push(CompatIssue {
+                severity: IssueSeverity::Error,
+                folder: m.folder.clone(),
+                mod_id: Some(info.id.clone()),
+                kind: CompatIssueKind::DuplicateId {
+                    folders: same.iter().map(|s| s.folder.clone()).collect(),
+                },
+            });
+        }
+    }
+    issues
+}
+
+pub fn scan_mods(mods_path: &std::path::Path) -> CommandResult<Vec<ScannedMod>> {
+    let mut mods: Vec<ScannedMod> = fs::read_dir(mods_path)?
+        .filter_map(Result::ok)
+        .filter(|e| e.path().is_dir())
+        .map(|e| {
+            let folder = e.path();
+            let (info, status) = read_mod_info(&folder);
+            ScannedMod {
+                folder,
+                info,
+                status,
+            }
+        })
+        .collect();
+    mods.sort_by(|a, b| a.folder.cmp(&b.folder));
+    Ok(mods)
+}
+
+/// Checks every mod in the Mods folder against each other, the game and Unity Mod Manager.
+#[command]
+pub fn check_mod_compatibility(
+    app_handle: AppHandle,
+    folder: Option<String>,
+) -> CommandResult<CompatibilityReport> {
+    let mods_path = settings::resolve_content_root(&app_handle, folder, ContentFolder::Mods)?;
+    let mods = scan_mods(&mods_path)?;
+    let game_version = settings::current(&app_handle).game_version;
+    let install_dir = game_install_dir(&app_handle);
+    let game_build_id = install_dir
+        .as_deref()
+        .and_then(|dir| installation_at(dir).build_id);
+    let manager_version = install_dir.and_then(|dir| {
+        detect_loader(
+            &dir,
+            LoaderKind::UnityModManager,
+            &load_loader_records(&app_handle),
+        )
+        .version
+    });
+
+    let issues = check_mods(&mods, game_version.as_deref(), manager_version.as_deref());
+    log::info!(
+        "[mod_compat::check_mod_compatibility] {} mods, {} issues",
+        mods.len(),
+        issues.len()
+    );
+    Ok(CompatibilityReport {
+        mods_path,
+        mods_checked: mods.len(),
+        game_version,
+        game_build_id,
+        manager_version,
+        issues,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scanned(folder: &str, id: &str, version: &str, requirements: &[&str]) -> ScannedMod {
+        ScannedMod {
+            folder: PathBuf::from(folder),
+            info: Some(ModInfo {
+                id: id.into(),
+                version: Some(version.into()),
+                requirements: requirements.iter().map(|r| r.to_string()).collect(),
+                ..Default::default()
+            }),
+            status: ManifestStatus::Valid,
+        }
+    }
+
+    #[test]
+    fn parses_requirements() {
+        assert_eq!(parse_requirement("XLGraphics"), ("XLGraphics", None));
+        assert_eq!(
+            parse_requirement("XLGraphics-1.2.0"),
+            ("XLGraphics", Some("1.2.0"))
+        );
+        assert_eq!(parse_requirement("Some-Mod-0.4"), ("Some-Mod", Some("0.4")));
+        assert_eq!(parse_requirement("XL-2DTricks"), ("XL-2DTricks", None));
+        assert_eq!(parse_requirement("XL-2"), ("XL-2", None));
+        assert_eq!(parse_requirement("Mod-1..2"), ("Mod-1..2", None));
+        assert_eq!(parse_requirement("-1.0"), ("-1.0", None));
+    }
+
+    #[test]
+    fn compares_versions() {
+        assert_eq!(compare_versions("1.2", "1.2.0"), Ordering::Equal);
+        assert_eq!(compare_versions("v1.10.0", "1.9.9"), Ordering::Greater);
+        assert_eq!(compare_versions("0.9", "1.0"), Ordering::Less);
+        assert_eq!(compare_versions("1.2.0-beta", "1.2.0"), Ordering::Equal);
+        assert_eq!(compare_versions("", "0"), Ordering::Equal);
+    }
+
+    #[test]
+    fn reports_missing_and_outdated_requirements() {
+        let mods = [
+            scanned("Mods/A", "A", "1.0", &["B-1.2.0", "C", "XL-2DTricks"]),
+            scanned("Mods/B", "b", "1.1.9", &[]),
+            scanned("Mods/XL", "XL-2DTricks", "1.0", &["B-1.1"]),
+        ];
+        let issues = check_mods(&mods, None, None);
+        assert_eq!(issues.len(), 2);
+        assert!(issues.iter().all(|i| i.mod_id.as_deref() == Some("A")));
+        assert!(matches!(
+            &issues[0].kind,
+            CompatIssueKind::OutdatedRequirement { required_id, required_version, installed_version }
+                if required_id == "B" && required_version == "1.2.0" && installed_version == "1.1.9"
+        ));
+        assert!(matches!(
+            &issues[1].kind,
+            CompatIssueKind::MissingRequirement { required_id } if required_id == "C"
+        ));
+    }
+
+    #[test]
+    fn reports_duplicate_ids_and_broken_manifests() {
+        let mods = [
+            scanned("Mods/One", "Walk", "1.0", &[]),
+            scanned("Mods/Two", "walk", "1.1", &[]),
+            ScannedMod {
+                folder: PathBuf::from("Mods/Empty"),
+                info: None,
+                status: ManifestStatus::Missing,
+            },
+        ];
+        let issues = check_mods(&mods, Some("1.2.2.8"), Some("0.27.0"));
+        assert!(matches!(issues[0].kind, CompatIssueKind::MissingManifest));
+        let duplicates: Vec<_> = issues
+            .iter()
+            .filter_map(|i| match &i.kind {
+                CompatIssueKind::DuplicateId { folders } => Some((&i.folder, folders.len())),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            duplicates,
+            [
+                (&PathBuf::from("Mods/One"), 2),
+                (&PathBuf::from("Mods/Two"), 2)
+            ]
+        );
+        assert_eq!(issues.len(), 3);
+    }
+
+    #[test]
+    fn checks_game_and_manager_versions() {
+        let mut m = scanned("Mods/A", "A", "1.0", &[]);
+        if let Some(info) = m.info.as_mut() {
+            info.game_version = Some("1.2.6".into());
+            info.manager_version = Some("0.27.0".into());
+        }
+        let issues = check_mods(std::slice::from_ref(&m), Some("1.2.2.8"), Some("0.25.0"));
+        assert_eq!(issues.len(), 2);
+        assert_eq!(issues[0].severity, IssueSeverity::Warning);
+        assert_eq!(issues[1].severity, IssueSeverity::Error);
+        assert!(check_mods(&[m], None, None).is_empty());
+    }
+}
@@ -0,0 +1,790 @@
+// src-tauri/src/mod_loaders.rs
+
+//! Mod loaders in the game install: detects Unity Mod Manager and BepInEx, installs or
+//! updates them from a release archive, and records every file written so an install can
+//! be undone exactly.
+
+use crate::assembly_info::read_assembly_identity;
+use crate::error::{CommandError, CommandResult};
+use crate::umm::game_install_dir;
+use crate::utils::system_time_to_millis;
+use serde::{Deserialize, Serialize};
+use std::{
+    fs::{self, File},
+    io::{copy, sink, Read},
+    path::{Component, Path, PathBuf},
+    time::SystemTime,
+};
+use tauri::{command, AppHandle, Manager};
+use zip::ZipArchive;
+
+const LOADER_RECORDS_FILE: &str = "loader_installs.json";
+const LOADER_BACKUPS_DIR: &str = "loader_backups";
+/// Unity Doorstop proxy files; both loaders use them to hook into the game at startup.
+const DOORSTOP_FILES: [&str; 3] = ["winhttp.dll", "version.dll", "doorstop_config.ini"];
+const DOORSTOP_CONFIG: &str = "doorstop_config.ini";
+/// The proxy UMM's own installer uses in its Doorstop mode.
+const UMM_PROXY: &str = "winhttp.dll";
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum LoaderKind {
+    UnityModManager,
+    BepInEx,
+}
+
+impl LoaderKind {
+    /// The loader's main assembly, relative to the game folder.
+    fn marker(self) -> PathBuf {
+        match self {
+            LoaderKind::UnityModManager => PathBuf::from("SkaterXL_Data")
+                .join("Managed")
+                .join("UnityModManager")
+                .join("UnityModManager.dll"),
+            LoaderKind::BepInEx => PathBuf::from("BepInEx").join("core").join("BepInEx.dll"),
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            LoaderKind::UnityModManager => "UnityModManager",
+            LoaderKind::BepInEx => "BepInEx",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct LoaderStatus {
+    pub kind: LoaderKind,
+    pub installed: bool,
+    /// The assembly version, else the version recorded at install.
+    pub version: Option<String>,
+    pub path: PathBuf,
+    /// Installed by XLFM, so it can be uninstalled from here.
+    pub managed: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct LoaderFileChange {
+    /// Relative to the game folder.
+    pub path: PathBuf,
+    /// Copy of the file this install overwrote, restored on uninstall.
+    pub backup: Option<PathBuf>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct LoaderInstallRecord {
+    pub kind: LoaderKind,
+    pub version: Option<String>,
+    pub game_dir: PathBuf,
+    pub installed_at: Option<u64>,
+    pub files: Vec<LoaderFileChange>,
+    /// Folders this install created, removed on uninstall when empty.
+    pub created_dirs: Vec<PathBuf>,
+}
+
+fn app_data_file(app_handle: &AppHandle, name: &str) -> CommandResult<PathBuf> {
+    app_handle
+        .path()
+        .app_data_dir()
+        .map(|dir| dir.join(name))
+        .map_err(|e| CommandError::DirectoryResolution(e.to_string()))
+}
+
+pub fn load_loader_records(app_handle: &AppHandle) -> Vec<LoaderInstallRecord> {
+    let Ok(path) = app_data_file(app_handle, LOADER_RECORDS_FILE) else {
+        return Vec::new();
+    };
+    match fs::read(&path) {
+        Ok(bytes) => serde_json::from_slice(&bytes).unwrap_or_else(|e| {
+            log::warn!(
+                "[mod_loaders::load_records] Ignoring corrupt records file {}: {}",
+                path.display(),
+                e
+            );
+            Vec::new()
+        }),
+        Err(_) => Vec::new(),
+    }
+}
+
+fn save_loader_records(
+    app_handle: &AppHandle,
+    records: &[LoaderInstallRecord],
+) -> CommandResult<()> {
+    let path = app_data_file(app_handle, LOADER_RECORDS_FILE)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let json = serde_json::to_vec_pretty(records)
+        .map_err(|e| CommandError::Input(format!("Failed to serialize loader records: {}", e)))?;
+    fs::write(&path, json)?;
+    Ok(())
+}
+
+fn require_game_dir(app_handle: &AppHandle) -> CommandResult<PathBuf> {
+    game_install_dir(app_handle).ok_or_else(|| {
+        CommandError::DirectoryResolution(
+            "Skater XL install not found; set the game install path in settings".into(),
+        )
+    })
+}
+
+pub fn detect_loader(
+    game_dir: &Path,
+    kind: LoaderKind,
+    records: &[LoaderInstallRecord],
+) -> LoaderStatus {
+    let path = game_dir.join(kind.marker());
+    let installed = path.is_file();
+    let record = records
+        .iter()
+        .find(|r| r.kind == kind && r.game_dir == game_dir);
+    let version = installed
+        .then(|| read_assembly_identity(&path))
+        .flatten()
+        .map(|identity| identity.version)
+        .or_else(|| record.and_then(|r| r.version.clone()));
+    LoaderStatus {
+        kind,
+        installed,
+        version,
+        path,
+        managed: record.is_some(),
+    }
+}
+
+/// Maps an archive entry to its place in the game folder, or `None` to skip it.
+/// UMM archives ship the manager folder (next to the installer exe), which belongs in
+/// `Managed/UnityModManager`; BepInEx archives mirror the game folder from some prefix.
+/// Doorstop files at the top of the prefix go to the game folder, except UMM's config,
+/// which is written by [`umm_doorstop_config`] instead.
+fn target_for(kind: LoaderKind, prefix: &Path, entry: &Path) -> Option<PathBuf> {
+    let relative = entry.strip_prefix(prefix).ok()?;
+    if relative.as_os_str().is_empty() {
+        return None;
+    }
+    let name = relative.file_name()?.to_string_lossy().to_lowercase();
+    if relative.components().count() == 1 && DOORSTOP_FILES.contains(&name.as_str()) {
+        if kind == LoaderKind::UnityModManager && name == DOORSTOP_CONFIG {
+            return None;
+        }
+        return Some(relative.to_path_buf());
+    }
+    match kind {
+        LoaderKind::UnityModManager => Some(
+            PathBuf::from("SkaterXL_Data")
+                .join("Managed")
+                .join("UnityModManager")
+                .join(relative),
+        ),
+        LoaderKind::BepInEx => Some(relative.to_path_buf()),
+    }
+}
+
+/// Finds the archive folder the loader's files hang off: the folder holding
+/// `UnityModManager.dll` for UMM, the parent of `BepInEx/` for BepInEx.
+fn archive_prefix(kind: LoaderKind, names: &[PathBuf]) -> Option<PathBuf> {
+    names.iter().find_map(|name| match kind {
+        LoaderKind::UnityModManager => name
+            .file_name()
+            .filter(|f| f.eq_ignore_ascii_case("UnityModManager.dll"))
+            .and(name.parent())
+            .map(Path::to_path_buf),
+        LoaderKind::BepInEx => {
+            let components: Vec<Component> = name.components().collect();
+            components
+                .iter()
+                .position(|c| c.as_os_str().eq_ignore_ascii_case("BepInEx"))
+                .filter(|&i| i + 1 < components.len())
+                .map(|i| components[..i].iter().collect())
+        }
+    })
+}
+
+/// Doorstop config that makes the proxy load UMM at startup, the same step UMM's installer
+/// performs in its Doorstop mode. Both the 3.x and 4.x section names are written, so
+/// either proxy version finds it.
+fn umm_doorstop_config() -> String {
+    let target = LoaderKind::UnityModManager
+        .marker()
+        .iter()
+        .map(|c| c.to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("\\");
+    format!(
+        "[UnityDoorstop]\nenabled=true\ntargetAssembly={0}\n\n[General]\nenabled=true\ntarget_assembly={0}\n",
+        target
+    )
+}
+
+/// A release archive that has been checked to hold a complete, readable loader.
+struct Release {
+    archive: ZipArchive<File>,
+    prefix: PathBuf,
+}
+
+/// Opens and validates a release before anything in the game folder is touched: the
+/// loader must be found, every entry must decompress and pass its checksum, and UMM
+/// releases must carry the Doorstop proxy, since without it nothing would load UMM.
+fn open_release(kind: LoaderKind, archive_path: &Path) -> CommandResult<Release> {
+    let mut archive = ZipArchive::new(File::open(archive_path)?)?;
+    let names: Vec<PathBuf> = (0..archive.len())
+        .filter_map(|i| {
+            archive
+                .by_index(i)
+                .ok()?
+                .enclosed_name()
+                .map(Path::to_path_buf)
+        })
+        .collect();
+    let prefix = archive_prefix(kind, &names).ok_or_else(|| {
+        CommandError::Input(format!(
+            "{} does not look like a {} release",
+            archive_path.display(),
+            kind.label()
+        ))
+    })?;
+    if kind == LoaderKind::UnityModManager
+        && !names
+            .iter()
+            .filter_map(|name| target_for(kind, &prefix, name))
+            .any(|target| target.as_os_str().eq_ignore_ascii_case(UMM_PROXY))
+    {
+        return Err(CommandError::Input(format!(
+            "{} has no {} next to UnityModManager.dll; run UnityModManager.exe from the \
+             release to install it instead",
+            archive_path.display(),
+            UMM_PROXY
+        )));
+    }
+    for i in 0..archive.len() {
+        copy(&mut archive.by_index(i)?, &mut sink())?;
+    }
+    Ok(Release { archive, prefix })
+}
+
+fn backup_dir(app_handle: &AppHandle, kind: LoaderKind) -> CommandResult<PathBuf> {
+    let stamp = system_time_to_millis(Some(SystemTime::now())).unwrap_or_default();
+    Ok(app_data_file(app_handle, LOADER_BACKUPS_DIR)?
+        .join(kind.label())
+        .join(stamp.to_string()))
+}
+
+/// Reverts a recorded install: removes written files, restores what they replaced and
+/// removes folders the install created if nothing else ended up in them.
+fn revert_install(record: &LoaderInstallRecord) -> CommandResult<()> {
+    for change in record.files.iter().rev() {
+        revert_change(&record.game_dir, change)?;
+    }
+    for dir in record.created_dirs.iter().rev() {
+        // Fails harmlessly when the loader wrote configs or plugins into it.
+        let _ = fs::remove_dir(record.game_dir.join(dir));
+    }
+    Ok(())
+}
+
+/// Removes one written file and puts back what it replaced.
+fn revert_change(game_dir: &Path, change: &LoaderFileChange) -> CommandResult<()> {
+    let path = game_dir.join(&change.path);
+    if path.is_file() {
+        fs::remove_file(&path)?;
+    }
+    if let Some(backup) = &change.backup {
+        if backup.is_file() {
+            fs::copy(backup, &path)?;
+            let _ = fs::remove_file(backup);
+        } else {
+            log::warn!(
+                "[mod_loaders::revert] Backup missing for {}",
+                path.display()
+            );
+        }
+    }
+    Ok(())
+}
+
+/// What an update leaves of the install it replaced, cleared once the new record is saved.
+struct Superseded {
+    /// The previous install's files the new version no longer ships, and its folders.
+    stale: LoaderInstallRecord,
+    /// Copies of the old loader's files the new install backed up, no longer needed.
+    unused_backups: Vec<PathBuf>,
+}
+
+/// Folds a previous install into the one that replaced it. Files the new version
+/// overwrote keep the previous record's backup, so uninstalling still restores the game's
+/// originals rather than the old loader. Nothing is removed here: that waits for
+/// [`remove_superseded`], once the merged record is saved.
+fn supersede_install(
+    previous: LoaderInstallRecord,
+    record: &mut LoaderInstallRecord,
+) -> Superseded {
+    let mut stale = Vec::new();
+    let mut unused_backups = Vec::new();
+    for old in previous.files {
+        match record.files.iter_mut().find(|c| c.path == old.path) {
+            Some(change) => {
+                unused_backups.extend(change.backup.take());
+                change.backup = old.backup;
+            }
+            None => stale.push(old),
+        }
+    }
+    let mut created_dirs = previous.created_dirs.clone();
+    created_dirs.append(&mut record.created_dirs);
+    record.created_dirs = created_dirs;
+    Superseded {
+        stale: LoaderInstallRecord {
+            files: stale,
+            ..previous
+        },
+        unused_backups,
+    }
+}
+
+/// Reverts the files a superseded install had that the new version dropped, and removes
+/// its folders if they ended up empty.
+fn remove_superseded(superseded: Superseded) {
+    for backup in superseded.unused_backups {
+        let _ = fs::remove_file(backup);
+    }
+    let stale = superseded.stale;
+    for old in stale.files.iter().rev() {
+        if let Err(e) = revert_change(&stale.game_dir, old) {
+            log::warn!(
+                "[mod_loaders::supersede] Could not remove stale {}: {}",
+                old.path.display(),
+                e
+            );
+        }
+    }
+    for dir in stale.created_dirs.iter().rev() {
+        let _ = fs::remove_dir(stale.game_dir.join(dir));
+    }
+}
+
+/// Writes one file into the game folder, recording it and backing up the file it replaces.
+fn write_game_file(
+    record: &mut LoaderInstallRecord,
+    backups: &Path,
+    relative: PathBuf,
+    contents: &mut impl Read,
+) -> CommandResult<()> {
+    let game_dir = record.game_dir.clone();
+    let dest = game_dir.join(&relative);
+    if let Some(parent) = relative.parent() {
+        let mut missing: Vec<PathBuf> = parent
+            .ancestors()
+            .filter(|a| !a.as_os_str().is_empty() && !game_dir.join(a).exists())
+            .map(Path::to_path_buf)
+            .collect();
+        missing.reverse();
+        fs::create_dir_all(game_dir.join(parent))?;
+        record.created_dirs.extend(missing);
+    }
+    let backup = if dest.is_file() {
+        let backup = backups.join(&relative);
+        if let Some(parent) = backup.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::copy(&dest, &backup)?;
+        Some(backup)
+    } else {
+        None
+    };
+    record.files.push(LoaderFileChange {
+        path: relative,
+        backup,
+    });
+    copy(contents, &mut File::create(&dest)?)?;
+    Ok(())
+}
+
+/// Writes a release into `game_dir`, backing up replaced files under `backups`. A failed
+/// write rolls back everything written so far.
+fn install_archive(
+    game_dir: &Path,
+    backups: &Path,
+    kind: LoaderKind,
+    release: Release,
+    version: Option<String>,
+) -> CommandResult<LoaderInstallRecord> {
+    let Release {
+        mut archive,
+        prefix,
+    } = release;
+
+    let mut record = LoaderInstallRecord {
+        kind,
+        version,
+        game_dir: game_dir.to_path_buf(),
+        installed_at: system_time_to_millis(Some(SystemTime::now())),
+        files: Vec::new(),
+        created_dirs: Vec::new(),
+    };
+    let result = (|| -> CommandResult<()> {
+        for i in 0..archive.len() {
+            let mut entry = archive.by_index(i)?;
+            if entry.is_dir() {
+                continue;
+            }
+            let Some(name) = entry.enclosed_name().map(Path::to_path_buf) else {
+                continue;
+            };
+            let Some(relative) = target_for(kind, &prefix, &name) else {
+                continue;
+            };
+            write_game_file(&mut record, backups, relative, &mut entry)?;
+        }
+        if kind == LoaderKind::UnityModManager {
+            let config = umm_doorstop_config();
+            write_game_file(
+                &mut record,
+                backups,
+                PathBuf::from(DOORSTOP_CONFIG),
+                &mut config.as_bytes(),
+            )?;
+        }
+        Ok(())
+    })();
+
+    if let Err(e) = result {
+        log::error!("[mod_loaders::install] Rolling back: {}", e);
+        if let Err(revert_error) = revert_install(&record) {
+            log::error!(
+                "[mod_loaders::install] Rollback incomplete: {}",
+                revert_error
+            );
+        }
+        return Err(e);
+    }
+    Ok(record)
+}
+
+/// Reports which loaders are present in the game install and their versions.
+#[command]
+pub fn detect_mod_loaders(app_handle: AppHandle) -> CommandResult<Vec<LoaderStatus>> {
+    let game_dir = require_game_dir(&app_handle)?;
+    let records = load_loader_records(&app_handle);
+    Ok([LoaderKind::UnityModManager, LoaderKind::BepInEx]
+        .into_iter()
+        .map(|kind| detect_loader(&game_dir, kind, &records))
+        .collect())
+}
+
+/// Installs a loader from its release zip, or updates it when XLFM installed it before.
+/// The archive is validated first and written over the previous install; only once the
+/// new record is saved are files the old version had but the new one lacks removed, so a
+/// failed update leaves the previous install working.
+#[command]
+pub fn install_mod_loader(
+    app_handle: AppHandle,
+    kind: LoaderKind,
+    archive_path: String,
+    version: Option<String>,
+) -> CommandResult<LoaderStatus> {
+    let game_dir = require_game_dir(&app_handle)?;
+    let release = open_release(kind, Path::new(&archive_path))?;
+    let backups = backup_dir(&app_handle, kind)?;
+    let mut records = load_loader_records(&app_handle);
+
+    let installed = install_archive(&game_dir, &backups, kind, release, version)?;
+    let mut record = installed.clone();
+    let superseded = records
+        .iter()
+        .position(|r| r.kind == kind && r.game_dir == game_dir)
+        .map(|pos| {
+            log::info!(
+                "[mod_loaders::install] Replacing previous {} install",
+                kind.label()
+            );
+            supersede_install(records.remove(pos), &mut record)
+        });
+    records.push(record);
+    if let Err(e) = save_loader_records(&app_handle, &records) {
+        // An unrecorded install could never be uninstalled. Reverting it puts back the
+        // previous install's files, which its saved record still describes.
+        log::error!(
+            "[mod_loaders::install] Could not record install, rolling back: {}",
+            e
+        );
+        if let Err(revert_error) = revert_install(&installed) {
+            log::error!(
+                "[mod_loaders::install] Rollback incomplete: {}",
+                revert_error
+            );
+        }
+        return Err(e);
+    }
+    if let Some(superseded) = superseded {
+        remove_superseded(superseded);
+    }
+    log::info!(
+        "[mod_loaders::install] {} installed into {} ({} files)",
+        kind.label(),
+        game_dir.display(),
+        installed.files.len()
+    );
+    Ok(detect_loader(&game_dir, kind, &records))
+}
+
+/// Undoes an install made by XLFM, restoring any files it replaced.
+#[command]
+pub fn uninstall_mod_loader(
+    app_handle: AppHandle,
+    kind: LoaderKind,
+) -> CommandResult<LoaderStatus> {
+    let game_dir = require_game_dir(&app_handle)?;
+    let mut records = load_loader_records(&app_handle);
+    let pos = records
+        .iter()
+        .position(|r| r.kind == kind && r.game_dir == game_dir)
+        .ok_or_else(|| {
+            CommandError::Input(format!("{} was not installed by XLFM", kind.label()))
+        })?;
+    revert_install(&records.remove(pos))?;
+    save_loader_records(&app_handle, &records)?;
+    log::info!("[mod_loaders::uninstall] {} removed", kind.label());
+    Ok(detect_loader(&game_dir, kind, &records))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use zip::{write::FileOptions, ZipWriter};
+
+    fn temp_dir() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("xlfm-loaders-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    /// Writes a BepInEx release zip holding `files` and opens it.
+    fn bepinex_release(root: &Path, name: &str, files: &[(&str, &str)]) -> Release {
+        let path = root.join(name);
+        let mut zip = ZipWriter::new(File::create(&path).unwrap());
+        for (entry, contents) in files {
+            zip.start_file(*entry, FileOptions::default()).unwrap();
+            zip.write_all(contents.as_bytes()).unwrap();
+        }
+        zip.finish().unwrap();
+        open_release(LoaderKind::BepInEx, &path).unwrap()
+    }
+
+    fn paths(names: &[&str]) -> Vec<PathBuf> {
+        names.iter().map(PathBuf::from).collect()
+    }
+
+    #[test]
+    fn finds_the_loader_folder_in_release_archives() {
+        let umm = paths(&[
+            "UnityModManager.exe",
+            "UnityModManager/winhttp.dll",
+            "UnityModManager/UnityModManager.dll",
+        ]);
+        assert_eq!(
+            archive_prefix(LoaderKind::UnityModManager, &umm),
+            Some(PathBuf::from("UnityModManager"))
+        );
+
+        let bepinex = paths(&[
+            "BepInEx_x64/winhttp.dll",
+            "BepInEx_x64/BepInEx/core/BepInEx.dll",
+        ]);
+        assert_eq!(
+            archive_prefix(LoaderKind::BepInEx, &bepinex),
+            Some(PathBuf::from("BepInEx_x64"))
+        );
+        let flat = paths(&["winhttp.dll", "BepInEx/core/BepInEx.dll"]);
+        assert_eq!(
+            archive_prefix(LoaderKind::BepInEx, &flat),
+            Some(PathBuf::new())
+        );
+
+        // A bare `BepInEx` file is not the loader folder.
+        assert_eq!(
+            archive_prefix(LoaderKind::BepInEx, &paths(&["BepInEx"])),
+            None
+        );
+        assert_eq!(archive_prefix(LoaderKind::UnityModManager, &bepinex), None);
+    }
+
+    #[test]
+    fn maps_archive_entries_into_the_game_folder() {
+        let prefix = Path::new("UnityModManager");
+        let umm = |entry: &str| target_for(LoaderKind::UnityModManager, prefix, Path::new(entry));
+        assert_eq!(
+            umm("UnityModManager/UnityModManager.dll"),
+            Some(LoaderKind::UnityModManager.marker())
+        );
+        assert_eq!(
+            umm("UnityModManager/WinHttp.dll"),
+            Some(PathBuf::from("WinHttp.dll"))
+        );
+        // UMM's config is generated, and entries outside the prefix are skipped.
+        assert_eq!(umm("UnityModManager/doorstop_config.ini"), None);
+        assert_eq!(umm("UnityModManager.exe"), None);
+        assert_eq!(umm("UnityModManager"), None);
+
+        let prefix = Path::new("BepInEx_x64");
+        let bepinex = |entry: &str| target_for(LoaderKind::BepInEx, prefix, Path::new(entry));
+        assert_eq!(
+            bepinex("BepInEx_x64/doorstop_config.ini"),
+            Some(PathBuf::from("doorstop_config.ini"))
+        );
+        assert_eq!(
+            bepinex("BepInEx_x64/BepInEx/core/BepInEx.dll"),
+            Some(LoaderKind::BepInEx.marker())
+        );
+    }
+
+    #[test]
+    fn umm_doorstop_config_targets_the_manager_assembly() {
+        let config = umm_doorstop_config();
+        assert!(config.contains(
+            "targetAssembly=SkaterXL_Data\\Managed\\UnityModManager\\UnityModManager.dll\n"
+        ));
+        assert!(config.contains(
+            "target_assembly=SkaterXL_Data\\Managed\\UnityModManager\\UnityModManager.dll\n"
+        ));
+    }
+
+    #[test]
+    fn uninstall_restores_the_files_an_install_replaced() {
+        let root = temp_dir();
+        let game = root.join("Skater XL");
+        fs::create_dir(&game).unwrap();
+        fs::write(game.join("winhttp.dll"), "original").unwrap();
+        let release = bepinex_release(
+            &root,
+            "v1.zip",
+            &[
+                ("BepInEx_x64/winhttp.dll", "v1 proxy"),
+                ("BepInEx_x64/BepInEx/core/BepInEx.dll", "v1"),
+            ],
+        );
+
+        let record = install_archive(
+            &game,
+            &root.join("backups"),
+            LoaderKind::BepInEx,
+            release,
+            None,
+        )
+        .unwrap();
+        assert_eq!(
+            fs::read_to_string(game.join("winhttp.dll")).unwrap(),
+            "v1 proxy"
+        );
+        assert!(game.join(LoaderKind::BepInEx.marker()).is_file());
+
+        revert_install(&record).unwrap();
+        assert_eq!(
+            fs::read_to_string(game.join("winhttp.dll")).unwrap(),
+            "original"
+        );
+        assert!(!game.join("BepInEx").exists());
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn update_keeps_the_first_backups_and_drops_removed_files() {
+        let root = temp_dir();
+        let game = root.join("Skater XL");
+        fs::create_dir(&game).unwrap();
+        fs::write(game.join("winhttp.dll"), "original").unwrap();
+        let v1 = bepinex_release(
+            &root,
+            "v1.zip",
+            &[
+                ("winhttp.dll", "v1 proxy"),
+                ("BepInEx/core/BepInEx.dll", "v1"),
+                ("BepInEx/core/Legacy.dll", "v1"),
+            ],
+        );
+        let v2 = bepinex_release(
+            &root,
+            "v2.zip",
+            &[
+                ("winhttp.dll", "v2 proxy"),
+                ("BepInEx/core/BepInEx.dll", "v2"),
+            ],
+        );
+        let first = install_archive(
+            &game,
+            &root.join("backups/1"),
+            LoaderKind::BepInEx,
+            v1,
+            None,
+        )
+        .unwrap();
+        let mut record = install_archive(
+            &game,
+            &root.join("backups/2"),
+            LoaderKind::BepInEx,
+            v2,
+            None,
+        )
+        .unwrap();
+
+        remove_superseded(supersede_install(first, &mut record));
+        assert!(!game.join("BepInEx/core/Legacy.dll").exists());
+        assert_eq!(
+            fs::read_to_string(game.join("winhttp.dll")).unwrap(),
+            "v2 proxy"
+        );
+        let proxy = record
+            .files
+            .iter()
+            .find(|c| c.path == Path::new("winhttp.dll"))
+            .unwrap();
+        assert_eq!(proxy.backup, Some(root.join("backups/1/winhttp.dll")));
+        assert!(!root.join("backups/2/winhttp.dll").exists());
+
+        revert_install(&record).unwrap();
+        assert_eq!(
+            fs::read_to_string(game.join("winhttp.dll")).unwrap(),
+            "original"
+        );
+        assert!(!game.join("BepInEx").exists());
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn failed_install_rolls_back() {
+        let root = temp_dir();
+        let game = root.join("Skater XL");
+        fs::create_dir_all(game.join("doorstop_config.ini")).unwrap();
+        fs::write(game.join("winhttp.dll"), "original").unwrap();
+        let release = bepinex_release(
+            &root,
+            "v1.zip",
+            &[
+                ("winhttp.dll", "v1 proxy"),
+                ("BepInEx/core/BepInEx.dll", "v1"),
+                ("doorstop_config.ini", "[General]"),
+            ],
+        );
+
+        let result = install_archive(
+            &game,
+            &root.join("backups"),
+            LoaderKind::BepInEx,
+            release,
+            None,
+        );
+        assert!(result.is_err());
+        assert_eq!(
+            fs::read_to_string(game.join("winhttp.dll")).unwrap(),
+            "original"
+        );
+        assert!(!game.join("BepInEx").exists());
+        assert!(game.join("doorstop_config.ini").is_dir());
+        fs::remove_dir_all(root).unwrap();
+    }
+}
@@ -43,6 +43,112 @@ pub struct FsEntry {
     pub thumbnail_path: Option<PathBuf>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub thumbnail_mime_type: Option<String>,
+    /// A 640x360 render of the same image for detail views.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub thumbnail_large_path: Option<PathBuf>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub modio_match: Option<ModioMatch>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<MapMetadata>,
+    /// Only set by map listings; `false` for maps parked in the disabled store and
+    /// unset for library maps that are not linked into the Maps folder.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+    /// Only set by map listings; the registered library root the entry comes from.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub library_root: Option<PathBuf>,
+    /// Only set by mod listings: the parsed Unity Mod Manager `Info.json`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mod_info: Option<ModInfo>,
+    /// Only set by mod listings, for folders: whether `Info.json` was usable.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub manifest_status: Option<ManifestStatus>,
+}
+
+/// A Unity Mod Manager `Info.json`. Read with UMM's PascalCase keys, sent to the UI in camelCase.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(rename_all(serialize = "camelCase", deserialize = "PascalCase"))]
+pub struct ModInfo {
+    pub id: String,
+    #[serde(default)]
+    pub display_name: Option<String>,
+    #[serde(default)]
+    pub author: Option<String>,
+    #[serde(default)]
+    pub version: Option<String>,
+    #[serde(default)]
+    pub manager_version: Option<String>,
+    #[serde(default)]
+    pub game_version: Option<String>,
+    /// Other mod Ids, optionally with a minimum version: `"OtherMod"` or `"OtherMod-1.2.0"`.
+    #[serde(default, deserialize_with = "null_as_default")]
+    pub requirements: Vec<String>,
+    #[serde(default, deserialize_with = "null_as_default")]
+    pub load_after: Vec<String>,
+    #[serde(default)]
+    pub assembly_name: Option<String>,
+    #[serde(default)]
+    pub entry_method: Option<String>,
+    #[serde(default)]
+    pub home_page: Option<String>,
+}
+
+/// Hand-written manifests often say `null` where they mean "none".
+fn null_as_default<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: serde::Deserializer<'de>,
+    T: Default + Deserialize<'de>,
+{
+    Ok(Option::<T>::deserialize(deserializer)?.unwrap_or_default())
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(tag = "status", rename_all = "camelCase")]
+pub enum ManifestStatus {
+    Valid,
+    Missing,
+    Malformed { message: String },
+}
+
+/// Contents of a map's `.xlfm.json` sidecar.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct MapMetadata {
+    #[serde(default)]
+    pub author: Option<String>,
+    #[serde(default)]
+    pub modio_id: Option<u64>,
+    #[serde(default)]
+    pub version: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Milliseconds since UNIX_EPOCH.
+    #[serde(default)]
+    pub installed_at: Option<u64>,
+    /// 1–5 stars, set by the user.
+    #[serde(default)]
+    pub user_rating: Option<u8>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum MatchMethod {
+    InstallRecord,
+    Metadata,
+    Filename,
+    NameSimilarity,
+}
+
+/// Link between a local entry and a mod.io catalog entry.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ModioMatch {
+    pub mod_id: u64,
+    /// 0.0–1.0; 1.0 means XLFM installed this exact path from the catalog.
+    pub confidence: f64,
+    pub method: MatchMethod,
 }
 
 #[derive(Clone, Serialize, Deserialize, Debug)]
@@ -68,6 +174,47 @@ pub struct InstallationResult {
     pub source: String,
 }
 
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum RelocateMode {
+    /// Move the contents; the original folder is removed once the link works.
+    Move,
+    /// Copy the contents; the original folder is kept as a `_backup` directory.
+    Copy,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RelocationProgress {
+    /// scanning, transferring, verifying, linking, cleanup, complete or rollback.
+    pub phase: String,
+    pub bytes_done: u64,
+    pub bytes_total: u64,
+    pub files_done: u64,
+    pub files_total: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub current: Option<String>,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct RelocationResult {
+    #[serde(
+        serialize_with = "serialize_pathbuf",
+        deserialize_with = "deserialize_pathbuf"
+    )]
+    pub new_location: PathBuf,
+    pub files_transferred: u64,
+    pub bytes_transferred: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(
+        default,
+        serialize_with = "serialize_optional_pathbuf",
+        deserialize_with = "deserialize_optional_pathbuf"
+    )]
+    pub backup_path: Option<PathBuf>,
+}
+
 // --- Helper functions for PathBuf serialization ---
 
 fn serialize_pathbuf<S>(path: &PathBuf, serializer: S) -> Result<S::Ok, S::Error>
@@ -0,0 +1,124 @@
+// src-tauri/src/modio_catalog.rs
+
+//! Rust-side mirror of the mod.io map catalog fetched by the frontend.
+//! The webview pushes its catalog here so listing commands can link local content to it.
+
+use crate::error::{CommandError, CommandResult};
+use crate::state::ModioCatalogState;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use tauri::{command, AppHandle, Manager, State};
+
+const CATALOG_CACHE_FILE: &str = "modio_catalog.json";
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct ModioUser {
+    #[serde(default)]
+    pub id: u64,
+    #[serde(default)]
+    pub username: String,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct ModioLogo {
+    #[serde(default)]
+    pub original: Option<String>,
+    #[serde(default)]
+    pub thumb_320x180: Option<String>,
+    #[serde(default)]
+    pub thumb_640x360: Option<String>,
+}
+
+impl ModioLogo {
+    /// The largest logo that still downloads quickly, for use as a thumbnail source.
+    pub fn preview_url(&self) -> Option<&str> {
+        self.thumb_640x360
+            .as_deref()
+            .or(self.original.as_deref())
+            .or(self.thumb_320x180.as_deref())
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct ModioModfile {
+    pub id: u64,
+    #[serde(default)]
+    pub filesize: Option<u64>,
+    #[serde(default)]
+    pub filesize_uncompressed: Option<u64>,
+    #[serde(default)]
+    pub filename: Option<String>,
+    #[serde(default)]
+    pub version: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct ModioTag {
+    pub name: String,
+}
+
+/// The subset of a mod.io `Mod` object XLFM cares about.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct ModioMod {
+    pub id: u64,
+    pub name: String,
+    #[serde(default)]
+    pub name_id: String,
+    #[serde(default)]
+    pub summary: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub profile_url: String,
+    #[serde(default)]
+    pub submitted_by: Option<ModioUser>,
+    #[serde(default)]
+    pub logo: Option<ModioLogo>,
+    #[serde(default)]
+    pub modfile: Option<ModioModfile>,
+    #[serde(default)]
+    pub tags: Vec<ModioTag>,
+}
+
+/// Replaces the in-memory catalog and persists it so matches survive restarts.
+#[command]
+pub fn sync_modio_catalog(
+    app_handle: AppHandle,
+    mods: Vec<ModioMod>,
+    state: State<'_, ModioCatalogState>,
+) -> CommandResult<()> {
+    log::info!("[modio_catalog::sync] Received {} mods", mods.len());
+    let cache_dir = app_handle
+        .path()
+        .app_cache_dir()
+        .map_err(|e| CommandError::DirectoryResolution(e.to_string()))?;
+    fs::create_dir_all(&cache_dir)?;
+    let json = serde_json::to_vec(&mods)
+        .map_err(|e| CommandError::Input(format!("Failed to serialize catalog: {}", e)))?;
+    fs::write(cache_dir.join(CATALOG_CACHE_FILE), json)?;
+
+    *state.mods.lock().unwrap() = mods;
+    Ok(())
+}
+
+/// Loads the catalog persisted by the last `sync_modio_catalog` call, if any.
+pub fn load_cached_catalog(app_handle: &AppHandle) {
+    let Ok(cache_dir) = app_handle.path().app_cache_dir() else {
+        return;
+    };
+    let path = cache_dir.join(CATALOG_CACHE_FILE);
+    let Ok(bytes) = fs::read(&path) else {
+        return;
+    };
+    match serde_json::from_slice::<Vec<ModioMod>>(&bytes) {
+        Ok(mods) => {
+            log::info!("[modio_catalog::load] Loaded {} cached mods", mods.len());
+            *app_handle.state::<ModioCatalogState>().mods.lock().unwrap() = mods;
+        }
+        Err(e) => log::warn!(
+            "[modio_catalog::load] Ignoring unreadable catalog cache {}: {}",
+            path.display(),
+            e
+        ),
+    }
+}
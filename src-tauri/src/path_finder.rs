@@ -1,118 +1,696 @@
+use crate::settings;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashSet,
+    fs,
+    path::{Path, PathBuf},
+    time::UNIX_EPOCH,
+};
+
 const SKATER_XL_APP_ID: u32 = 962730;
+const USER_DATA_DIR: &str = "SkaterXL";
 
-/// Finds the Skater XL user data directory.
-/// This function is platform-agnostic and handles three cases:
-/// 1. Native Windows installations.
-/// 2. Linux installations via Steam Play (Proton).
-/// 3. Native macOS (returns None as the game is not supported).
-#[tauri::command]
-pub fn find_skaterxl_user_data_path() -> Option<String> {
-    #[cfg(target_os = "windows")]
-    {
-        // On Windows, the path is straightforward: %USERPROFILE%\Documents\SkaterXL
-        // We use the `dirs` crate to find the user's document directory reliably.
-        let docs_path = match dirs::document_dir() {
-            Some(path) => path,
-            None => return None, // Cannot find Documents folder
-        };
+/// Where a user data candidate came from.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum CandidateSource {
+    /// `Documents\SkaterXL` on Windows.
+    Documents,
+    /// A Proton prefix in a Steam library reported by `steamlocate`.
+    SteamLibrary,
+    /// A Proton prefix under a well-known native Steam root (`~/.steam/steam`, `~/.local/share/Steam`).
+    SteamDefault,
+    /// Flatpak Steam (`~/.var/app/com.valvesoftware.Steam`).
+    Flatpak,
+    /// Snap Steam (`~/snap/steam`).
+    Snap,
+    /// The prefix named by `STEAM_COMPAT_DATA_PATH`.
+    CompatDataEnv,
+    /// A Wine, Lutris or Heroic prefix the user pointed us at.
+    CustomPrefix,
+}
 
-        let candidate = docs_path.join("SkaterXL");
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct UserDataCandidate {
+    pub path: PathBuf,
+    pub source: CandidateSource,
+    /// The Steam root or prefix the candidate was derived from.
+    pub origin: PathBuf,
+}
 
-        if candidate.exists() {
-            return Some(candidate.to_string_lossy().into_owned());
+/// `Documents/SkaterXL` inside a Wine prefix, for every user the prefix has.
+/// Proton prefixes use `steamuser`; Wine, Lutris and Heroic use the login name.
+fn prefix_user_data(prefix: &Path) -> Vec<PathBuf> {
+    // Accept both a prefix and a Proton compatdata folder that wraps one in `pfx`.
+    let prefix = if prefix.join("pfx").is_dir() {
+        prefix.join("pfx")
+    } else {
+        prefix.to_path_buf()
+    };
+    let users = prefix.join("drive_c").join("users");
+    let mut paths = vec![users
+        .join("steamuser")
+        .join("Documents")
+        .join(USER_DATA_DIR)];
+    if let Ok(entries) = fs::read_dir(&users) {
+        for entry in entries.filter_map(Result::ok) {
+            let name = entry.file_name();
+            if name == "steamuser" || name == "Public" {
+                continue;
+            }
+            paths.push(entry.path().join("Documents").join(USER_DATA_DIR));
         }
     }
+    paths
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct SteamLibrary {
+    pub path: PathBuf,
+    pub source: CandidateSource,
+}
+
+/// Why a candidate path was not used.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub enum CandidateRejection {
+    Missing,
+    NotADirectory,
+    /// Resolves to a folder an earlier candidate already found.
+    Duplicate,
+    /// Exists, but an earlier candidate was chosen.
+    NotChosen,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CheckedCandidate {
+    #[serde(flatten)]
+    pub candidate: UserDataCandidate,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rejection: Option<CandidateRejection>,
+}
+
+/// Everything auto-detection looked at, so a failed search can be explained in the UI.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct DiscoveryReport {
+    pub steam_libraries: Vec<SteamLibrary>,
+    pub candidates: Vec<CheckedCandidate>,
+    pub chosen: Option<UserDataCandidate>,
+    /// Problems that did not stop the search, e.g. Steam not being installed.
+    pub warnings: Vec<String>,
+}
+
+impl DiscoveryReport {
+    fn warn(&mut self, message: String) {
+        log::warn!("[path_finder::discover_user_data] {}", message);
+        self.warnings.push(message);
+    }
+
+    /// Existing user data folders, in priority order.
+    pub fn found(&self) -> impl Iterator<Item = &UserDataCandidate> {
+        self.candidates
+            .iter()
+            .filter(|c| matches!(c.rejection, None | Some(CandidateRejection::NotChosen)))
+            .map(|c| &c.candidate)
+    }
+}
+
+/// Steam installations worth probing on this machine, tagged with how they were found.
+fn steam_roots(report: &mut DiscoveryReport) -> Vec<(PathBuf, CandidateSource)> {
+    let mut roots = Vec::new();
+    match steamlocate::SteamDir::locate() {
+        Ok(steamdir) => {
+            log::debug!(
+                "[path_finder::steam_roots] Steam located at {}",
+                steamdir.path().display()
+            );
+            roots.push((steamdir.path().to_path_buf(), CandidateSource::SteamLibrary));
+        }
+        Err(e) => report.warn(format!("steamlocate could not find Steam: {}", e)),
+    }
+    if let Some(home) = dirs::home_dir() {
+        roots.extend(home_steam_roots(&home));
+    }
+    distinct_dirs(roots)
+}
+
+/// The native, Flatpak and Snap Steam roots under a home folder.
+fn home_steam_roots(home: &Path) -> Vec<(PathBuf, CandidateSource)> {
+    let flatpak = home
+        .join(".var")
+        .join("app")
+        .join("com.valvesoftware.Steam");
+    let snap = home.join("snap").join("steam").join("common");
+    vec![
+        (
+            home.join(".steam").join("steam"),
+            CandidateSource::SteamDefault,
+        ),
+        (
+            home.join(".local").join("share").join("Steam"),
+            CandidateSource::SteamDefault,
+        ),
+        (
+            flatpak.join(".local").join("share").join("Steam"),
+            CandidateSource::Flatpak,
+        ),
+        (flatpak.join("data").join("Steam"), CandidateSource::Flatpak),
+        (
+            snap.join(".local").join("share").join("Steam"),
+            CandidateSource::Snap,
+        ),
+        (snap.join(".steam").join("steam"), CandidateSource::Snap),
+    ]
+}
+
+/// Drops roots that do not exist or resolve to a folder an earlier root already named.
+fn distinct_dirs(mut roots: Vec<(PathBuf, CandidateSource)>) -> Vec<(PathBuf, CandidateSource)> {
+    let mut seen = HashSet::new();
+    roots.retain(|(root, _)| {
+        root.is_dir() && seen.insert(fs::canonicalize(root).unwrap_or(root.clone()))
+    });
+    roots
+}
+
+fn steam_libraries(report: &mut DiscoveryReport) -> Vec<SteamLibrary> {
+    let roots = steam_roots(report);
+    libraries_of(roots, report)
+}
+
+/// Every library folder of every Steam root, tagged with the root's source.
+fn libraries_of(
+    roots: Vec<(PathBuf, CandidateSource)>,
+    report: &mut DiscoveryReport,
+) -> Vec<SteamLibrary> {
+    let mut libraries = Vec::new();
+    for (root, source) in roots {
+        libraries.push(SteamLibrary {
+            path: root.clone(),
+            source,
+        });
+        match steamlocate::SteamDir::from_dir(&root).and_then(|s| s.libraries()) {
+            Ok(found) => libraries.extend(found.filter_map(Result::ok).map(|l| SteamLibrary {
+                path: l.path().to_path_buf(),
+                source,
+            })),
+            Err(e) => report.warn(format!(
+                "Could not read the library list of {}: {}",
+                root.display(),
+                e
+            )),
+        }
+    }
+    let mut seen = HashSet::new();
+    libraries.retain(|lib| {
+        lib.path.is_dir() && seen.insert(fs::canonicalize(&lib.path).unwrap_or(lib.path.clone()))
+    });
+    for lib in &libraries {
+        log::debug!(
+            "[path_finder::steam_libraries] Steam library ({:?}): {}",
+            lib.source,
+            lib.path.display()
+        );
+    }
+    libraries
+}
+
+/// Every place the user data folder could be, in priority order, whether or not it exists.
+#[cfg_attr(not(target_os = "linux"), allow(unused_variables))]
+fn user_data_candidates(
+    custom_prefixes: &[PathBuf],
+    compat_data: Option<&Path>,
+    libraries: &[SteamLibrary],
+) -> Vec<UserDataCandidate> {
+    let mut candidates = Vec::new();
+    let mut push_prefix = |prefix: &Path, source: CandidateSource| {
+        for path in prefix_user_data(prefix) {
+            candidates.push(UserDataCandidate {
+                path,
+                source,
+                origin: prefix.to_path_buf(),
+            });
+        }
+    };
+
+    for prefix in custom_prefixes {
+        push_prefix(prefix, CandidateSource::CustomPrefix);
+    }
+    if let Some(compat) = compat_data {
+        push_prefix(compat, CandidateSource::CompatDataEnv);
+    }
+
+    #[cfg(target_os = "windows")]
+    if let Some(docs) = dirs::document_dir() {
+        candidates.push(UserDataCandidate {
+            path: docs.join(USER_DATA_DIR),
+            source: CandidateSource::Documents,
+            origin: docs,
+        });
+    }
 
     #[cfg(target_os = "linux")]
-    {
-        // On Linux, the game runs through Proton, so we must find the "compatdata" directory.
-        // The `steamlocate` crate reliably finds all Steam library folders.
-
-        if let Ok(steamdir) = steamlocate::SteamDir::locate() {
-            println!("[LOG] SteamDir located successfully: {:?}", steamdir.path());
-
-            // 1. Check the main Steam directory first
-            let main_steam_candidate = steamdir.path()
-                .join("steamapps")
-                .join("compatdata")
-                .join(SKATER_XL_APP_ID.to_string())
-                .join("pfx")
-                .join("drive_c")
-                .join("users")
-                .join("steamuser")
-                .join("Documents")
-                .join("SkaterXL");
-
-            println!("[LOG] Constructed candidate path (main SteamDir): {:?}", &main_steam_candidate);
-            if main_steam_candidate.exists() {
-                println!("[LOG] Found Linux (Proton) user data path at (main SteamDir): {:?}", &main_steam_candidate);
-                return Some(main_steam_candidate.to_string_lossy().into_owned());
-            } else {
-                println!("[LOG] Candidate path (main SteamDir) does not exist: {:?}", &main_steam_candidate);
-            }
+    for library in libraries {
+        let compat = library
+            .path
+            .join("steamapps")
+            .join("compatdata")
+            .join(SKATER_XL_APP_ID.to_string());
+        for path in prefix_user_data(&compat) {
+            candidates.push(UserDataCandidate {
+                path,
+                source: library.source,
+                origin: library.path.clone(),
+            });
+        }
+    }
+    candidates
+}
+
+/// Looks for the Skater XL user data folder everywhere we know of and records every step.
+/// This covers native Windows, Proton in any Steam flavour, and custom Wine prefixes.
+/// macOS has no native build, so there is nothing to find there.
+pub fn discover_user_data(custom_prefixes: &[PathBuf]) -> DiscoveryReport {
+    let mut report = DiscoveryReport::default();
+    report.steam_libraries = steam_libraries(&mut report);
+    let compat_data = std::env::var_os("STEAM_COMPAT_DATA_PATH").map(PathBuf::from);
+    let candidates = user_data_candidates(
+        custom_prefixes,
+        compat_data.as_deref(),
+        &report.steam_libraries,
+    );
+    check_candidates(&mut report, candidates);
 
-            // 2. If not found in main, check additional Steam libraries
-            if let Ok(libraries) = steamdir.libraries() {
-                println!("[LOG] Found {} additional Steam libraries.", libraries.len());
-                for library_result in libraries {
-                    if let Ok(library) = library_result {
-                        println!("[LOG] Checking additional Steam library path: {:?}", library.path());
-                        let library_candidate = library.path()
-                            .join("steamapps")
-                            .join("compatdata")
-                            .join(SKATER_XL_APP_ID.to_string())
-                            .join("pfx")
-                            .join("drive_c")
-                            .join("users")
-                            .join("steamuser")
-                            .join("Documents")
-                            .join("SkaterXL");
-
-                        println!("[LOG] Constructed candidate path (additional library): {:?}", &library_candidate);
-
-                        if library_candidate.exists() {
-                            println!("[LOG] Found Linux (Proton) user data path at (additional library): {:?}", &library_candidate);
-                            return Some(library_candidate.to_string_lossy().into_owned());
-                        } else {
-                            println!("[LOG] Candidate path (additional library) does not exist: {:?}", &library_candidate);
-                        }
-                    } else {
-                        println!("[LOG] Failed to get Steam library path from result.");
-                    }
+    match &report.chosen {
+        Some(c) => log::info!(
+            "[path_finder::discover_user_data] Using user data folder ({:?}) {}",
+            c.source,
+            c.path.display()
+        ),
+        None => log::warn!(
+            "[path_finder::discover_user_data] No user data folder found after checking {} candidates",
+            report.candidates.len()
+        ),
+    }
+    report
+}
+
+/// Records every candidate in `report`, choosing the first existing folder.
+fn check_candidates(report: &mut DiscoveryReport, candidates: Vec<UserDataCandidate>) {
+    let mut seen = HashSet::new();
+    for candidate in candidates {
+        let rejection = match fs::metadata(&candidate.path) {
+            Err(_) => Some(CandidateRejection::Missing),
+            Ok(m) if !m.is_dir() => Some(CandidateRejection::NotADirectory),
+            Ok(_) => {
+                let real = fs::canonicalize(&candidate.path).unwrap_or(candidate.path.clone());
+                if !seen.insert(real) {
+                    Some(CandidateRejection::Duplicate)
+                } else if report.chosen.is_some() {
+                    Some(CandidateRejection::NotChosen)
+                } else {
+                    report.chosen = Some(candidate.clone());
+                    None
                 }
-            } else {
-                println!("[LOG] Failed to get additional Steam libraries.");
             }
-        } else {
-            println!("[LOG] Failed to locate Steam directory via steamlocate.");
+        };
+        log::debug!(
+            "[path_finder::discover_user_data] Candidate ({:?}) {}: {:?}",
+            candidate.source,
+            candidate.path.display(),
+            rejection
+        );
+        report.candidates.push(CheckedCandidate {
+            candidate,
+            rejection,
+        });
+    }
+}
+
+/// Finds the Skater XL user data directory: the first candidate that exists.
+#[tauri::command]
+pub fn find_skaterxl_user_data_path() -> Option<String> {
+    discover_user_data(&[])
+        .chosen
+        .map(|c| c.path.to_string_lossy().into_owned())
+}
+
+/// Lists every user data folder found, including ones inside `custom_prefixes`.
+#[tauri::command]
+pub fn find_skaterxl_user_data_candidates(
+    custom_prefixes: Option<Vec<String>>,
+) -> Vec<UserDataCandidate> {
+    let report = discover_skaterxl_paths(custom_prefixes);
+    report.found().cloned().collect()
+}
+
+/// The full auto-detection report, for the settings page to show when detection fails.
+#[tauri::command]
+pub fn discover_skaterxl_paths(custom_prefixes: Option<Vec<String>>) -> DiscoveryReport {
+    let prefixes: Vec<PathBuf> = custom_prefixes
+        .unwrap_or_default()
+        .into_iter()
+        .map(PathBuf::from)
+        .collect();
+    discover_user_data(&prefixes)
+}
+
+/// Where the game itself is installed, as opposed to its user data folder.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct GameInstallation {
+    /// `steamapps/common/Skater XL` (or whatever the manifest's `installdir` says).
+    pub install_dir: Option<PathBuf>,
+    /// The Steam library folder that holds the game.
+    pub steam_library: Option<PathBuf>,
+    pub manifest_path: Option<PathBuf>,
+    /// Steam build ID; changes with every game update.
+    pub build_id: Option<u64>,
+    /// Seconds since the epoch, as recorded by Steam.
+    pub last_updated: Option<u64>,
+    /// Only filled by `find_game_installation`, from the settings.
+    pub user_data_path: Option<PathBuf>,
+}
+
+/// What a library's app manifest says about the game.
+fn installation_from(app: &steamlocate::App, library: &steamlocate::Library) -> GameInstallation {
+    let manifest_path = library
+        .path()
+        .join("steamapps")
+        .join(format!("appmanifest_{}.acf", SKATER_XL_APP_ID));
+    GameInstallation {
+        install_dir: Some(library.resolve_app_dir(app)).filter(|d| d.is_dir()),
+        steam_library: Some(library.path().to_path_buf()),
+        manifest_path: Some(manifest_path).filter(|p| p.is_file()),
+        build_id: app.build_id,
+        last_updated: app
+            .last_updated
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map(|d| d.as_secs()),
+        user_data_path: None,
+    }
+}
+
+/// Looks for the game in the libraries of one Steam root.
+fn installation_in(root: &Path) -> Option<GameInstallation> {
+    let found =
+        steamlocate::SteamDir::from_dir(root).and_then(|steam| steam.find_app(SKATER_XL_APP_ID));
+    match found {
+        Ok(found) => found.map(|(app, library)| installation_from(&app, &library)),
+        Err(e) => {
+            log::warn!(
+                "[path_finder::locate_game_installation] Could not search {}: {}",
+                root.display(),
+                e
+            );
+            None
         }
+    }
+}
 
-        // 3. Fallback: Check common Steam path directly if steamlocate fails or doesn't find it
-        if let Some(home_dir) = dirs::home_dir() {
-            let common_steam_path = home_dir.join(".steam")
-                                            .join("steam")
-                                            .join("steamapps")
-                                            .join("compatdata")
-                                            .join(SKATER_XL_APP_ID.to_string())
-                                            .join("pfx")
-                                            .join("drive_c")
-                                            .join("users")
-                                            .join("steamuser")
-                                            .join("Documents")
-                                            .join("Skater XL");
-
-            println!("[LOG] Constructed candidate path (fallback): {:?}", &common_steam_path);
-
-            if common_steam_path.exists() {
-                println!("[LOG] Found Linux (Proton) user data path at (fallback): {:?}", &common_steam_path);
-                return Some(common_steam_path.to_string_lossy().into_owned());
-            } else {
-                println!("[LOG] Candidate path (fallback) does not exist: {:?}", &common_steam_path);
-            }
+/// Finds the game's Steam install through the app manifest of whichever Steam root has
+/// it, reading the build ID and update time Steam recorded there.
+pub fn locate_game_installation() -> GameInstallation {
+    let mut report = DiscoveryReport::default();
+    let installation = steam_roots(&mut report)
+        .iter()
+        .find_map(|(root, _)| installation_in(root))
+        .unwrap_or_default();
+    if let Some(manifest) = &installation.manifest_path {
+        log::info!(
+            "[path_finder::locate_game_installation] Manifest found at {}",
+            manifest.display()
+        );
+    }
+    installation
+}
+
+/// The Steam details of the install at `install_dir`, from the manifest of the library it
+/// sits in (`<library>/steamapps/common/<game>`). Other installs only get `install_dir`.
+pub fn installation_at(install_dir: &Path) -> GameInstallation {
+    let same_dir = |a: &Path, b: &Path| {
+        fs::canonicalize(a)
+            .ok()
+            .is_some_and(|a| fs::canonicalize(b).is_ok_and(|b| a == b))
+    };
+    install_dir
+        .ancestors()
+        .nth(3)
+        .and_then(|library| steamlocate::Library::from_dir(library).ok())
+        .and_then(|library| {
+            let app = library.app(SKATER_XL_APP_ID)?.ok()?;
+            same_dir(&library.resolve_app_dir(&app), install_dir)
+                .then(|| installation_from(&app, &library))
+        })
+        .unwrap_or_else(|| GameInstallation {
+            install_dir: Some(install_dir.to_path_buf()),
+            ..Default::default()
+        })
+}
+
+/// The Steam install plus the user data folder the settings resolve to.
+#[tauri::command]
+pub fn find_game_installation(app_handle: tauri::AppHandle) -> GameInstallation {
+    GameInstallation {
+        user_data_path: settings::current(&app_handle).resolved_game_path(),
+        ..locate_game_installation()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("xlfm-paths-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    /// A Wine prefix with a `Documents/SkaterXL` folder for `user`.
+    fn prefix_with_user_data(prefix: &Path, user: &str) -> PathBuf {
+        let path = prefix
+            .join("drive_c")
+            .join("users")
+            .join(user)
+            .join("Documents")
+            .join(USER_DATA_DIR);
+        fs::create_dir_all(&path).unwrap();
+        path
+    }
+
+    /// A Steam root whose `libraryfolders.vdf` lists itself and `libraries`.
+    fn fake_steam_root(root: &Path, libraries: &[&Path]) {
+        fs::create_dir_all(root.join("steamapps")).unwrap();
+        let mut vdf = String::from("\"libraryfolders\"\n{\n");
+        for (i, library) in std::iter::once(root)
+            .chain(libraries.iter().copied())
+            .enumerate()
+        {
+            vdf.push_str(&format!(
+                "\t\"{}\"\n\t{{\n\t\t\"path\"\t\t\"{}\"\n\t\t\"apps\"\n\t\t{{\n\t\t}}\n\t}}\n",
+                i,
+                library.display()
+            ));
+        }
+        vdf.push_str("}\n");
+        fs::write(root.join("steamapps").join("libraryfolders.vdf"), vdf).unwrap();
+    }
+
+    /// Installs the game into a library with an app manifest.
+    fn fake_install(library: &Path) -> PathBuf {
+        let steamapps = library.join("steamapps");
+        let install_dir = steamapps.join("common").join("Skater XL");
+        fs::create_dir_all(&install_dir).unwrap();
+        fs::write(
+            steamapps.join(format!("appmanifest_{}.acf", SKATER_XL_APP_ID)),
+            format!(
+                "\"AppState\"\n{{\n\t\"appid\"\t\t\"{}\"\n\t\"name\"\t\t\"Skater XL\"\n\t\"installdir\"\t\t\"Skater XL\"\n\t\"LastUpdated\"\t\t\"1700000000\"\n\t\"buildid\"\t\t\"13571113\"\n}}\n",
+                SKATER_XL_APP_ID
+            ),
+        )
+        .unwrap();
+        install_dir
+    }
+
+    fn candidate(path: PathBuf, source: CandidateSource) -> UserDataCandidate {
+        UserDataCandidate {
+            path,
+            source,
+            origin: PathBuf::new(),
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn finds_flatpak_and_snap_roots_once() {
+        let home = temp_dir();
+        let native = home.join(".local").join("share").join("Steam");
+        let flatpak = home
+            .join(".var")
+            .join("app")
+            .join("com.valvesoftware.Steam")
+            .join("data")
+            .join("Steam");
+        let snap = home
+            .join("snap")
+            .join("steam")
+            .join("common")
+            .join(".local")
+            .join("share")
+            .join("Steam");
+        for dir in [&native, &flatpak, &snap] {
+            fs::create_dir_all(dir).unwrap();
         }
+        // `~/.steam/steam` is usually a link to the native root.
+        fs::create_dir_all(home.join(".steam")).unwrap();
+        std::os::unix::fs::symlink(&native, home.join(".steam").join("steam")).unwrap();
+
+        let roots = distinct_dirs(home_steam_roots(&home));
+        assert_eq!(
+            roots,
+            [
+                (
+                    home.join(".steam").join("steam"),
+                    CandidateSource::SteamDefault
+                ),
+                (flatpak, CandidateSource::Flatpak),
+                (snap, CandidateSource::Snap),
+            ]
+        );
+        fs::remove_dir_all(home).unwrap();
+    }
+
+    #[test]
+    fn reads_every_library_of_a_root() {
+        let dir = temp_dir();
+        let (root, games) = (dir.join("Steam"), dir.join("Games"));
+        fs::create_dir_all(games.join("steamapps")).unwrap();
+        fake_steam_root(&root, &[&games, &dir.join("Unplugged")]);
+
+        let mut report = DiscoveryReport::default();
+        let libraries = libraries_of(vec![(root.clone(), CandidateSource::Flatpak)], &mut report);
+        let paths: Vec<_> = libraries.iter().map(|l| l.path.clone()).collect();
+        assert_eq!(paths, [root.clone(), games]);
+        assert!(libraries
+            .iter()
+            .all(|l| l.source == CandidateSource::Flatpak));
+        assert!(report.warnings.is_empty());
+
+        let bare = dir.join("Bare");
+        fs::create_dir_all(&bare).unwrap();
+        let libraries = libraries_of(vec![(bare, CandidateSource::SteamDefault)], &mut report);
+        assert_eq!(libraries.len(), 1);
+        assert_eq!(report.warnings.len(), 1);
+        fs::remove_dir_all(dir).unwrap();
     }
 
-    // Skater XL is not available on macOS, and this is a fallback for other systems
-    // or if the path was not found in any of the checks above.
-    None
-}
\ No newline at end of file
+    #[test]
+    fn lists_users_of_a_prefix() {
+        let dir = temp_dir();
+        let compat = dir.join("compatdata");
+        let pfx = compat.join("pfx");
+        prefix_with_user_data(&pfx, "steamuser");
+        prefix_with_user_data(&pfx, "Public");
+        let alice = prefix_with_user_data(&pfx, "alice");
+
+        let paths = prefix_user_data(&compat);
+        assert_eq!(paths.len(), 2);
+        assert_eq!(paths[0], prefix_user_data(&pfx)[0]);
+        assert_eq!(paths[1], alice);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn orders_candidates_by_source() {
+        let (custom, compat) = (PathBuf::from("/wine/lutris"), PathBuf::from("/proton/env"));
+        let libraries = [SteamLibrary {
+            path: PathBuf::from("/steam"),
+            source: CandidateSource::Snap,
+        }];
+        let candidates =
+            user_data_candidates(std::slice::from_ref(&custom), Some(&compat), &libraries);
+        let sources: Vec<_> = candidates.iter().map(|c| c.source).collect();
+        let mut expected = vec![
+            CandidateSource::CustomPrefix,
+            CandidateSource::CompatDataEnv,
+        ];
+        #[cfg(target_os = "windows")]
+        expected.push(CandidateSource::Documents);
+        #[cfg(target_os = "linux")]
+        expected.push(CandidateSource::Snap);
+        assert_eq!(sources, expected);
+        assert_eq!(candidates[0].origin, custom);
+        assert_eq!(candidates[1].origin, compat);
+    }
+
+    #[test]
+    fn records_why_candidates_were_rejected() {
+        let dir = temp_dir();
+        let chosen = prefix_with_user_data(&dir.join("a"), "steamuser");
+        let other = prefix_with_user_data(&dir.join("b"), "steamuser");
+        let file = dir.join("file");
+        fs::write(&file, "").unwrap();
+
+        let mut report = DiscoveryReport::default();
+        check_candidates(
+            &mut report,
+            vec![
+                candidate(dir.join("missing"), CandidateSource::CustomPrefix),
+                candidate(file, CandidateSource::CustomPrefix),
+                candidate(chosen.clone(), CandidateSource::CustomPrefix),
+                candidate(chosen.join(".."), CandidateSource::CompatDataEnv),
+                candidate(
+                    chosen.join("..").join(USER_DATA_DIR),
+                    CandidateSource::CompatDataEnv,
+                ),
+                candidate(other.clone(), CandidateSource::SteamLibrary),
+            ],
+        );
+        let rejections: Vec<_> = report.candidates.iter().map(|c| c.rejection).collect();
+        assert_eq!(
+            rejections,
+            [
+                Some(CandidateRejection::Missing),
+                Some(CandidateRejection::NotADirectory),
+                None,
+                Some(CandidateRejection::NotChosen),
+                Some(CandidateRejection::Duplicate),
+                Some(CandidateRejection::NotChosen),
+            ]
+        );
+        assert_eq!(report.chosen.as_ref().map(|c| &c.path), Some(&chosen));
+        let found: Vec<_> = report.found().map(|c| c.path.clone()).collect();
+        assert_eq!(found, [chosen.clone(), chosen.join(".."), other]);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn reads_the_install_from_the_app_manifest() {
+        let dir = temp_dir();
+        let (root, games) = (dir.join("Steam"), dir.join("Games"));
+        fs::create_dir_all(games.join("steamapps")).unwrap();
+        fake_steam_root(&root, &[&games]);
+        assert!(installation_in(&root).is_none());
+
+        let install_dir = fake_install(&games);
+        let installation = installation_in(&root).unwrap();
+        assert_eq!(installation.install_dir, Some(install_dir.clone()));
+        assert_eq!(installation.steam_library, Some(games.clone()));
+        assert_eq!(installation.build_id, Some(13571113));
+        assert_eq!(installation.last_updated, Some(1700000000));
+        assert!(installation.manifest_path.is_some_and(|p| p.is_file()));
+        assert_eq!(installation.user_data_path, None);
+
+        assert_eq!(installation_at(&install_dir).build_id, Some(13571113));
+        let copy = dir.join("Copy").join("Skater XL");
+        fs::create_dir_all(&copy).unwrap();
+        let elsewhere = installation_at(&copy);
+        assert_eq!(elsewhere.install_dir, Some(copy));
+        assert_eq!(elsewhere.build_id, None);
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
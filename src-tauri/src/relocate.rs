@@ -0,0 +1,477 @@
+// src-tauri/src/relocate.rs
+
+//! Moves or copies a content folder to a new location, verifies the result, then replaces
+//! the original with a link. Any failure before the link is in place is rolled back.
+
+use crate::error::{CommandError, CommandResult};
+use crate::link_backend::{next_backup_path, LinkBackend};
+use crate::models::{RelocateMode, RelocationProgress, RelocationResult};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+use walkdir::WalkDir;
+
+/// One completed transfer step, kept so it can be undone.
+enum Transferred {
+    /// A top-level entry renamed into the destination (same volume, move mode).
+    Renamed { from: PathBuf, to: PathBuf },
+    /// A file copied into the destination.
+    CopiedFile(PathBuf),
+    /// A directory created in the destination.
+    CreatedDir(PathBuf),
+    /// A symlink recreated in the destination.
+    CreatedLink(PathBuf),
+}
+
+fn rollback(steps: &mut Vec<Transferred>) {
+    while let Some(step) = steps.pop() {
+        let result = match &step {
+            Transferred::Renamed { from, to } => fs::rename(to, from),
+            Transferred::CopiedFile(path) => fs::remove_file(path),
+            Transferred::CreatedDir(path) => fs::remove_dir(path),
+            // Windows directory symlinks are removed as directories.
+            Transferred::CreatedLink(path) => {
+                fs::remove_file(path).or_else(|_| fs::remove_dir(path))
+            }
+        };
+        if let Err(e) = result {
+            log::error!("[relocate::rollback] Undo step failed: {}", e);
+        }
+    }
+}
+
+fn progress(
+    phase: &str,
+    bytes_done: u64,
+    bytes_total: u64,
+    files_done: u64,
+    files_total: u64,
+    current: Option<&Path>,
+) -> RelocationProgress {
+    RelocationProgress {
+        phase: phase.to_string(),
+        bytes_done,
+        bytes_total,
+        files_done,
+        files_total,
+        current: current.map(|p| p.to_string_lossy().into_owned()),
+    }
+}
+
+/// Recreates the symlink `from` at `to` with the same, possibly relative, target.
+fn copy_link(from: &Path, to: &Path) -> std::io::Result<()> {
+    let target = fs::read_link(from)?;
+    #[cfg(windows)]
+    {
+        if fs::metadata(from).is_ok_and(|m| m.is_dir()) {
+            std::os::windows::fs::symlink_dir(&target, to)
+        } else {
+            std::os::windows::fs::symlink_file(&target, to)
+        }
+    }
+    #[cfg(not(windows))]
+    {
+        std::os::unix::fs::symlink(&target, to)
+    }
+}
+
+/// Copies `from` (file or directory tree) into `to`, recording every created path.
+/// Symlinks are recreated rather than followed.
+fn copy_tree(
+    from: &Path,
+    to: &Path,
+    steps: &mut Vec<Transferred>,
+    on_file: &mut dyn FnMut(&Path, u64),
+) -> CommandResult<()> {
+    for entry in WalkDir::new(from)
+        .follow_links(false)
+        .follow_root_links(false)
+    {
+        let entry = entry.map_err(|e| CommandError::Io(e.to_string()))?;
+        let rel = entry.path().strip_prefix(from).unwrap_or(Path::new(""));
+        let dest = if rel.as_os_str().is_empty() {
+            to.to_path_buf()
+        } else {
+            to.join(rel)
+        };
+        if entry.file_type().is_dir() {
+            fs::create_dir(&dest).map_err(|e| {
+                CommandError::Io(format!("Failed to create {}: {}", dest.display(), e))
+            })?;
+            steps.push(Transferred::CreatedDir(dest));
+        } else if entry.file_type().is_symlink() {
+            copy_link(entry.path(), &dest).map_err(|e| {
+                CommandError::Io(format!(
+                    "Failed to recreate link {} at {}: {}",
+                    entry.path().display(),
+                    dest.display(),
+                    e
+                ))
+            })?;
+            steps.push(Transferred::CreatedLink(dest));
+        } else {
+            let bytes = fs::copy(entry.path(), &dest).map_err(|e| {
+                CommandError::Io(format!(
+                    "Failed to copy {} to {}: {}",
+                    entry.path().display(),
+                    dest.display(),
+                    e
+                ))
+            })?;
+            steps.push(Transferred::CopiedFile(dest));
+            on_file(entry.path(), bytes);
+        }
+    }
+    Ok(())
+}
+
+/// Checks that every file under `source` exists under `dest` with the same size, and every
+/// symlink with the same target.
+fn verify_tree(source: &Path, dest: &Path) -> CommandResult<()> {
+    for entry in WalkDir::new(source)
+        .follow_links(false)
+        .follow_root_links(false)
+    {
+        let entry = entry.map_err(|e| CommandError::Io(e.to_string()))?;
+        if entry.file_type().is_dir() {
+            continue;
+        }
+        let rel = entry.path().strip_prefix(source).unwrap_or(Path::new(""));
+        let copy = if rel.as_os_str().is_empty() {
+            dest.to_path_buf()
+        } else {
+            dest.join(rel)
+        };
+        if entry.file_type().is_symlink() {
+            let expected = fs::read_link(entry.path()).ok();
+            if expected.is_none() || expected != fs::read_link(&copy).ok() {
+                return Err(CommandError::Io(format!(
+                    "Verification failed for link {}",
+                    copy.display()
+                )));
+            }
+            continue;
+        }
+        let expected = entry.metadata().map(|m| m.len()).ok();
+        let actual = fs::metadata(&copy).map(|m| m.len()).ok();
+        if expected.is_none() || expected != actual {
+            return Err(CommandError::Io(format!(
+                "Verification failed for {}: expected {:?} bytes, found {:?}",
+                copy.display(),
+                expected,
+                actual
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Copies `from` to `to` and verifies the copy, removing everything it created on failure.
+pub(crate) fn copy_verified(from: &Path, to: &Path) -> CommandResult<u64> {
+    let mut steps = Vec::new();
+    let mut files = 0;
+    let result = copy_tree(from, to, &mut steps, &mut |_, _| files += 1)
+        .and_then(|()| verify_tree(from, to));
+    if result.is_err() {
+        rollback(&mut steps);
+    }
+    result.map(|()| files)
+}
+
+/// Relocates the real directory at `link` into `new_folder`, then links `link` to it.
+///
+/// In `Move` mode entries are renamed where possible and the leftovers are deleted once the
+/// link exists. In `Copy` mode the original folder is kept as a `_backup` directory.
+pub fn relocate_folder(
+    backend: &impl LinkBackend,
+    link: &Path,
+    new_folder: &Path,
+    mode: RelocateMode,
+    on_progress: &mut dyn FnMut(RelocationProgress),
+) -> CommandResult<RelocationResult> {
+    if backend.is_link(link)? {
+        return Err(CommandError::Input(format!(
+            "{} is already a link; point it somewhere else instead of relocating",
+            link.display()
+        )));
+    }
+    if !link.is_dir() {
+        return Err(CommandError::Input(format!(
+            "Nothing to relocate, not a directory: {}",
+            link.display()
+        )));
+    }
+    fs::create_dir_all(new_folder)?;
+    let source = fs::canonicalize(link)?;
+    let dest = fs::canonicalize(new_folder)?;
+    if dest.starts_with(&source) || source.starts_with(&dest) {
+        return Err(CommandError::Input(
+            "The new location cannot be inside the current folder or contain it".into(),
+        ));
+    }
+
+    // --- Scan and check for name clashes before touching anything ---
+    on_progress(progress("scanning", 0, 0, 0, 0, None));
+    let top_level: Vec<PathBuf> = fs::read_dir(&source)?
+        .filter_map(Result::ok)
+        .map(|e| e.path())
+        .collect();
+    if let Some(clash) = top_level
+        .iter()
+        .find(|p| fs::symlink_metadata(dest.join(p.file_name().unwrap_or_default())).is_ok())
+    {
+        return Err(CommandError::Input(format!(
+            "'{}' already exists in {}",
+            clash.file_name().unwrap_or_default().to_string_lossy(),
+            dest.display()
+        )));
+    }
+    let (mut files_total, mut bytes_total) = (0u64, 0u64);
+    for entry in WalkDir::new(&source).min_depth(1).into_iter().filter_map(Result::ok) {
+        if entry.file_type().is_file() {
+            files_total += 1;
+            bytes_total += entry.metadata().map(|m| m.len()).unwrap_or(0);
+        }
+    }
+
+    // --- Transfer ---
+    let mut steps: Vec<Transferred> = Vec::new();
+    let (mut files_done, mut bytes_done) = (0u64, 0u64);
+    let transfer = (|| -> CommandResult<()> {
+        for entry in &top_level {
+            let target = dest.join(entry.file_name().unwrap_or_default());
+            if mode == RelocateMode::Move && fs::rename(entry, &target).is_ok() {
+                let (count, size) = WalkDir::new(&target)
+                    .into_iter()
+                    .filter_map(Result::ok)
+                    .filter(|e| e.file_type().is_file())
+                    .fold((0, 0), |(c, s), e| {
+                        (c + 1, s + e.metadata().map(|m| m.len()).unwrap_or(0))
+                    });
+                files_done += count;
+                bytes_done += size;
+                steps.push(Transferred::Renamed {
+                    from: entry.clone(),
+                    to: target.clone(),
+                });
+                on_progress(progress(
+                    "transferring",
+                    bytes_done,
+                    bytes_total,
+                    files_done,
+                    files_total,
+                    Some(entry),
+                ));
+                continue;
+            }
+            copy_tree(entry, &target, &mut steps, &mut |path, bytes| {
+                files_done += 1;
+                bytes_done += bytes;
+                on_progress(progress(
+                    "transferring",
+                    bytes_done,
+                    bytes_total,
+                    files_done,
+                    files_total,
+                    Some(path),
+                ));
+            })?;
+        }
+
+        on_progress(progress(
+            "verifying",
+            bytes_done,
+            bytes_total,
+            files_done,
+            files_total,
+            None,
+        ));
+        for entry in &top_level {
+            // Renamed entries are no longer in the source; only copies need checking.
+            if fs::symlink_metadata(entry).is_ok() {
+                verify_tree(entry, &dest.join(entry.file_name().unwrap_or_default()))?;
+            }
+        }
+        Ok(())
+    })();
+    if let Err(e) = transfer {
+        on_progress(progress("rollback", 0, bytes_total, 0, files_total, None));
+        rollback(&mut steps);
+        return Err(e);
+    }
+
+    // --- Swap the original folder for a link ---
+    on_progress(progress(
+        "linking",
+        bytes_done,
+        bytes_total,
+        files_done,
+        files_total,
+        None,
+    ));
+    let backup = next_backup_path(link);
+    if let Err(e) = fs::rename(link, &backup) {
+        rollback(&mut steps);
+        return Err(CommandError::Io(format!(
+            "Failed to move {} aside: {}",
+            link.display(),
+            e
+        )));
+    }
+    if let Err(e) = backend.create_dir_link(&dest, link) {
+        on_progress(progress("rollback", 0, bytes_total, 0, files_total, None));
+        if let Err(undo) = fs::rename(&backup, link) {
+            log::error!(
+                "[relocate::relocate_folder] Could not restore {}: {}",
+                link.display(),
+                undo
+            );
+        }
+        rollback(&mut steps);
+        return Err(e.into());
+    }
+
+    // --- Clean up ---
+    let backup_path = match mode {
+        RelocateMode::Copy => Some(backup),
+        RelocateMode::Move => {
+            on_progress(progress(
+                "cleanup",
+                bytes_done,
+                bytes_total,
+                files_done,
+                files_total,
+                None,
+            ));
+            match fs::remove_dir_all(&backup) {
+                Ok(()) => None,
+                Err(e) => {
+                    log::warn!(
+                        "[relocate::relocate_folder] Could not delete originals in {}: {}",
+                        backup.display(),
+                        e
+                    );
+                    Some(backup)
+                }
+            }
+        }
+    };
+
+    on_progress(progress(
+        "complete",
+        bytes_done,
+        bytes_total,
+        files_done,
+        files_total,
+        None,
+    ));
+    Ok(RelocationResult {
+        new_location: dest,
+        files_transferred: files_done,
+        bytes_transferred: bytes_done,
+        backup_path,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::link_backend::mock::MockLinkBackend;
+
+    fn temp_dir() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("xlfm-relocate-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    /// A Maps folder with a loose file and a map folder.
+    fn maps_folder(root: &Path) -> PathBuf {
+        let maps = root.join("Maps");
+        fs::create_dir_all(maps.join("Park")).unwrap();
+        fs::write(maps.join("Park").join("park_bundle"), "bundle").unwrap();
+        fs::write(maps.join("notes.txt"), "hi").unwrap();
+        maps
+    }
+
+    #[test]
+    fn move_transfers_everything_and_links_the_original() {
+        let root = temp_dir();
+        let maps = maps_folder(&root);
+        let target = root.join("Elsewhere");
+        let backend = MockLinkBackend::default();
+
+        let result =
+            relocate_folder(&backend, &maps, &target, RelocateMode::Move, &mut |_| {}).unwrap();
+        let dest = fs::canonicalize(&target).unwrap();
+        assert_eq!(result.new_location, dest);
+        assert_eq!((result.files_transferred, result.bytes_transferred), (2, 8));
+        assert_eq!(result.backup_path, None);
+        assert_eq!(
+            fs::read_to_string(dest.join("Park").join("park_bundle")).unwrap(),
+            "bundle"
+        );
+        assert_eq!(backend.read_link(&maps).unwrap(), dest);
+        assert!(!maps.exists());
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn copy_keeps_the_original_as_a_backup() {
+        let root = temp_dir();
+        let maps = maps_folder(&root);
+        let target = root.join("Elsewhere");
+        let backend = MockLinkBackend::default();
+
+        let result =
+            relocate_folder(&backend, &maps, &target, RelocateMode::Copy, &mut |_| {}).unwrap();
+        let backup = result.backup_path.unwrap();
+        assert!(backup.join("Park").join("park_bundle").is_file());
+        assert!(target.join("Park").join("park_bundle").is_file());
+        assert_eq!(fs::read_to_string(target.join("notes.txt")).unwrap(), "hi");
+        assert!(backend.is_link(&maps).unwrap());
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn failed_link_rolls_everything_back() {
+        let root = temp_dir();
+        let maps = maps_folder(&root);
+        let target = root.join("Elsewhere");
+        let backend = MockLinkBackend {
+            fail_create: true,
+            ..Default::default()
+        };
+
+        for mode in [RelocateMode::Move, RelocateMode::Copy] {
+            let mut phases = Vec::new();
+            let result = relocate_folder(&backend, &maps, &target, mode, &mut |p| {
+                phases.push(p.phase)
+            });
+            assert!(result.is_err());
+            assert!(phases.iter().any(|p| p == "rollback"));
+            assert!(maps.join("Park").join("park_bundle").is_file());
+            assert!(maps.join("notes.txt").is_file());
+            assert_eq!(fs::read_dir(&target).unwrap().count(), 0);
+        }
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn copies_symlinks_as_links() {
+        let root = temp_dir();
+        let maps = maps_folder(&root);
+        std::os::unix::fs::symlink("Park", maps.join("Park Alias")).unwrap();
+        let target = root.join("Elsewhere");
+        let backend = MockLinkBackend::default();
+
+        let result =
+            relocate_folder(&backend, &maps, &target, RelocateMode::Copy, &mut |_| {}).unwrap();
+        let alias = target.join("Park Alias");
+        assert!(fs::symlink_metadata(&alias).unwrap().is_symlink());
+        assert_eq!(fs::read_link(&alias).unwrap(), PathBuf::from("Park"));
+        assert_eq!(result.files_transferred, 2);
+        fs::remove_dir_all(root).unwrap();
+    }
+}
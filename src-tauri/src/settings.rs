@@ -0,0 +1,310 @@
+// src-tauri/src/settings.rs
+
+//! App settings owned by the Rust side, persisted as `settings.json` in the app config dir.
+//! Commands read paths and preferences from here instead of taking them from the webview.
+
+use crate::content_folders::ContentFolder;
+use crate::error::{CommandError, CommandResult};
+use crate::path_finder::discover_user_data;
+use crate::state::SettingsState;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::{
+    fs,
+    path::{Component, PathBuf},
+    sync::{Arc, OnceLock},
+};
+use tauri::{command, AppHandle, Emitter, Manager};
+
+const SETTINGS_FILE: &str = "settings.json";
+pub const SETTINGS_CHANGED_EVENT: &str = "settings-changed";
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase", default)]
+pub struct InstallPolicy {
+    /// Write a `.xlfm.json` sidecar next to maps installed from mod.io.
+    pub write_sidecars: bool,
+    /// Remember which mod.io file produced each install.
+    pub record_installs: bool,
+}
+
+impl Default for InstallPolicy {
+    fn default() -> Self {
+        Self {
+            write_sidecars: true,
+            record_installs: true,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Limits {
+    /// Upper bound for the thumbnail cache, in megabytes.
+    pub thumbnail_cache_mb: u64,
+    /// Background threads used to generate thumbnails.
+    pub thumbnail_workers: usize,
+}
+
+impl Default for Limits {
+    fn default() -> Self {
+        Self {
+            thumbnail_cache_mb: 256,
+            thumbnail_workers: 2,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Settings {
+    /// The SkaterXL user data folder; auto-detected when unset.
+    pub game_path: Option<PathBuf>,
+    /// The game's install folder (`steamapps/common/Skater XL`); auto-detected when unset.
+    pub game_install_path: Option<PathBuf>,
+    /// The game's version string (e.g. `1.2.2.8`), for mod `GameVersion` checks. Steam
+    /// only exposes a build ID, so this has to come from the user.
+    pub game_version: Option<String>,
+    /// Custom Maps folder; defaults to `<game_path>/Maps`.
+    pub maps_path: Option<PathBuf>,
+    /// Custom Mods folder; defaults to `<game_path>/Mods`.
+    pub mods_path: Option<PathBuf>,
+    /// Wine, Lutris or Heroic prefixes to search for the user data folder.
+    pub custom_prefixes: Vec<PathBuf>,
+    pub install: InstallPolicy,
+    pub limits: Limits,
+    /// The auto-detected SkaterXL folder, shared by every snapshot of these settings.
+    /// Only a successful search is kept; until then every lookup searches again.
+    #[serde(skip)]
+    detected_game_path: Arc<OnceLock<PathBuf>>,
+}
+
+impl Settings {
+    /// The configured game path, falling back to auto-detection.
+    pub fn resolved_game_path(&self) -> Option<PathBuf> {
+        self.game_path
+            .clone()
+            .filter(|p| !p.as_os_str().is_empty())
+            .or_else(|| {
+                self.detected_or(|| {
+                    discover_user_data(&self.custom_prefixes)
+                        .chosen
+                        .map(|c| c.path)
+                })
+            })
+    }
+
+    /// The cached detected game path, or the result of `search`, cached if found.
+    fn detected_or(&self, search: impl FnOnce() -> Option<PathBuf>) -> Option<PathBuf> {
+        if let Some(path) = self.detected_game_path.get() {
+            return Some(path.clone());
+        }
+        let found = search()?;
+        Some(self.detected_game_path.get_or_init(|| found).clone())
+    }
+
+    pub fn resolved_maps_path(&self) -> Option<PathBuf> {
+        self.maps_path
+            .clone()
+            .filter(|p| !p.as_os_str().is_empty())
+            .or_else(|| self.resolved_game_path().map(|p| p.join("Maps")))
+    }
+
+    pub fn resolved_mods_path(&self) -> Option<PathBuf> {
+        self.mods_path
+            .clone()
+            .filter(|p| !p.as_os_str().is_empty())
+            .or_else(|| self.resolved_game_path().map(|p| p.join("Mods")))
+    }
+
+    /// Where a content folder lives: Maps and Mods honour their custom paths, everything
+    /// else sits in the SkaterXL folder.
+    pub fn resolved_content_path(&self, kind: ContentFolder) -> Option<PathBuf> {
+        match kind {
+            ContentFolder::Maps => self.resolved_maps_path(),
+            ContentFolder::Mods => self.resolved_mods_path(),
+            other => self.resolved_game_path().map(|p| p.join(other.dir_name())),
+        }
+    }
+}
+
+/// Resolves a content folder argument without assuming it lives under Documents:
+/// - nothing: the configured or detected folder for `default`
+/// - a logical name (`"maps"`, `"mods"`, `"gear"`, …, or `"root"` for the SkaterXL folder)
+/// - an absolute path, used as is
+/// - a relative path, joined onto the SkaterXL folder (a leading `SkaterXL/` is dropped)
+pub fn resolve_content_root(
+    app_handle: &AppHandle,
+    spec: Option<String>,
+    default: ContentFolder,
+) -> CommandResult<PathBuf> {
+    let settings = current(app_handle);
+    let not_found = || {
+        CommandError::DirectoryResolution(
+            "No folder configured and the SkaterXL folder could not be detected".into(),
+        )
+    };
+    let spec = match spec.map(|s| s.trim().to_string()).filter(|s| !s.is_empty()) {
+        None => {
+            return settings
+                .resolved_content_path(default)
+                .ok_or_else(not_found)
+        }
+        Some(spec) => spec,
+    };
+
+    let lower = spec.to_lowercase();
+    if lower == "root" || lower == "skaterxl" {
+        return settings.resolved_game_path().ok_or_else(not_found);
+    }
+    if let Some(kind) = ContentFolder::ALL
+        .iter()
+        .find(|k| k.dir_name().eq_ignore_ascii_case(&lower))
+    {
+        return settings.resolved_content_path(*kind).ok_or_else(not_found);
+    }
+
+    let path = PathBuf::from(&spec);
+    if path.is_absolute() {
+        return Ok(path);
+    }
+    let relative: PathBuf = match path.components().next() {
+        Some(Component::Normal(first)) if first.eq_ignore_ascii_case("SkaterXL") => {
+            path.components().skip(1).collect()
+        }
+        _ => path,
+    };
+    if relative
+        .components()
+        .any(|c| !matches!(c, Component::Normal(_) | Component::CurDir))
+    {
+        return Err(CommandError::Input(format!(
+            "Relative content paths cannot leave the SkaterXL folder: {}",
+            spec
+        )));
+    }
+    Ok(settings
+        .resolved_game_path()
+        .ok_or_else(not_found)?
+        .join(relative))
+}
+
+fn settings_file(app_handle: &AppHandle) -> CommandResult<PathBuf> {
+    app_handle
+        .path()
+        .app_config_dir()
+        .map(|dir| dir.join(SETTINGS_FILE))
+        .map_err(|e| CommandError::DirectoryResolution(e.to_string()))
+}
+
+/// Loads settings from disk into managed state at startup. A missing or corrupt
+/// file falls back to defaults so the app can still start.
+pub fn load_settings(app_handle: &AppHandle) {
+    let loaded = settings_file(app_handle).and_then(|path| match fs::read(&path) {
+        Ok(bytes) => serde_json::from_slice::<Settings>(&bytes).map_err(|e| {
+            CommandError::Input(format!("Corrupt settings file {}: {}", path.display(), e))
+        }),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Settings::default()),
+        Err(e) => Err(e.into()),
+    });
+    let settings = loaded.unwrap_or_else(|e| {
+        log::warn!("[settings::load_settings] Using defaults: {}", e);
+        Settings::default()
+    });
+    *app_handle.state::<SettingsState>().settings.lock().unwrap() = settings;
+}
+
+/// A snapshot of the current settings.
+pub fn current(app_handle: &AppHandle) -> Settings {
+    app_handle
+        .try_state::<SettingsState>()
+        .map(|s| s.settings.lock().unwrap().clone())
+        .unwrap_or_default()
+}
+
+fn save_settings(app_handle: &AppHandle, settings: &Settings) -> CommandResult<()> {
+    let path = settings_file(app_handle)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let json = serde_json::to_vec_pretty(settings)
+        .map_err(|e| CommandError::Input(format!("Failed to serialize settings: {}", e)))?;
+    fs::write(&path, json)?;
+    Ok(())
+}
+
+/// Merges `patch` into `base`: objects merge key by key, anything else replaces.
+fn merge_json(base: &mut Value, patch: Value) {
+    match (base, patch) {
+        (Value::Object(base), Value::Object(patch)) => {
+            for (key, value) in patch {
+                merge_json(base.entry(key).or_insert(Value::Null), value);
+            }
+        }
+        (base, patch) => *base = patch,
+    }
+}
+
+#[command]
+pub fn get_settings(app_handle: AppHandle) -> Settings {
+    current(&app_handle)
+}
+
+/// Applies a partial update, e.g. `{ "mapsPath": "..." }` or `{ "limits": { "thumbnailCacheMb": 512 } }`,
+/// persists it and emits `settings-changed` with the result.
+#[command]
+pub fn update_settings(app_handle: AppHandle, patch: Value) -> CommandResult<Settings> {
+    let state = app_handle.state::<SettingsState>();
+    let updated = {
+        let mut settings = state.settings.lock().unwrap();
+        let mut merged = serde_json::to_value(&*settings)
+            .map_err(|e| CommandError::Input(format!("Failed to serialize settings: {}", e)))?;
+        merge_json(&mut merged, patch);
+        // Deserializing leaves the detected game path unset, so detection runs again
+        // against the new settings.
+        let updated: Settings = serde_json::from_value(merged)
+            .map_err(|e| CommandError::Input(format!("Invalid settings: {}", e)))?;
+        save_settings(&app_handle, &updated)?;
+        *settings = updated.clone();
+        updated
+    };
+    log::info!("[settings::update_settings] Settings saved");
+    if let Err(e) = app_handle.emit(SETTINGS_CHANGED_EVENT, &updated) {
+        log::warn!("[settings::update_settings] emit failed: {}", e);
+    }
+    Ok(updated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snapshots_share_the_detected_game_path() {
+        let settings = Settings::default();
+        let snapshot = settings.clone();
+        let detected = PathBuf::from("/games/SkaterXL");
+        settings.detected_game_path.set(detected.clone()).unwrap();
+        assert_eq!(snapshot.resolved_game_path(), Some(detected.clone()));
+        assert_eq!(snapshot.resolved_maps_path(), Some(detected.join("Maps")));
+
+        // An update deserializes fresh settings, which detect again.
+        let updated: Settings =
+            serde_json::from_value(serde_json::to_value(&settings).unwrap()).unwrap();
+        assert!(updated.detected_game_path.get().is_none());
+    }
+
+    #[test]
+    fn failed_detection_is_not_cached() {
+        let settings = Settings::default();
+        assert_eq!(settings.detected_or(|| None), None);
+        assert!(settings.detected_game_path.get().is_none());
+
+        let found = PathBuf::from("/mnt/external/SkaterXL");
+        assert_eq!(
+            settings.detected_or(|| Some(found.clone())),
+            Some(found.clone())
+        );
+        assert_eq!(settings.detected_or(|| unreachable!()), Some(found));
+    }
+}
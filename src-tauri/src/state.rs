@@ -25,3 +25,15 @@ impl Drop for WatcherState {
         let _ = self.update_tx.clone().try_send(WatcherCommand::Shutdown);
     }
 }
+
+/// mod.io catalog last pushed by the frontend, used to link local content to catalog entries.
+#[derive(Default)]
+pub struct ModioCatalogState {
+    pub mods: Mutex<Vec<crate::modio_catalog::ModioMod>>,
+}
+
+/// Current app settings; loaded from disk at startup, written through `update_settings`.
+#[derive(Default)]
+pub struct SettingsState {
+    pub settings: Mutex<crate::settings::Settings>,
+}
@@ -0,0 +1,790 @@
+// src-tauri/src/thumbnail_cache.rs
+
+//! The thumbnail cache under `app_cache/thumbnails`. Files live in one folder per content
+//! kind and are tracked in `index.json`, keyed by the item, the source image and the
+//! source's size and mtime, so an edited image gets a fresh entry. The whole cache is kept
+//! under `limits.thumbnailCacheMb` by evicting the least recently used files. Entries are
+//! downscaled renders (see `thumbnail_render`), written in the background when a pool runs.
+//! Sources that failed to decode are remembered with a marker file. mod.io logos used as
+//! fallbacks are downloaded into `logos/`, one per mod ID.
+
+use crate::content_folders::ContentFolder;
+use crate::error::{CommandError, CommandResult};
+use crate::settings;
+use crate::thumbnail_render::{
+    failure_marker, preview_file, render_thumbnail, ThumbnailJob, ThumbnailPool, ThumbnailSize,
+    OUTPUT_EXTENSION,
+};
+use crate::utils::{hash_str, normalize_path, system_time_to_millis, THUMBNAIL_EXTS};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{HashMap, HashSet},
+    ffi::OsStr,
+    fs,
+    path::{Path, PathBuf},
+    sync::Mutex,
+    time::SystemTime,
+};
+use tauri::{command, AppHandle, Manager};
+
+const INDEX_FILE: &str = "index.json";
+const LOGOS_DIR: &str = "logos";
+/// Bumped when keys change. Version 1 keys came from `DefaultHasher`, which is not stable
+/// across Rust releases; version 2 keys are SHA-256.
+const INDEX_VERSION: u32 = 2;
+
+/// Serializes index read-modify-write cycles between concurrent listings.
+static INDEX_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheEntry {
+    pub kind: ContentFolder,
+    /// The map, mod or other item the thumbnail belongs to.
+    pub item: PathBuf,
+    pub source: PathBuf,
+    /// Zero, with no mtime, for downloaded logos.
+    pub source_size: u64,
+    pub source_modified: Option<u64>,
+    pub size: ThumbnailSize,
+    /// Relative to the cache root.
+    pub file: PathBuf,
+    /// Zero while the render is still queued.
+    pub bytes: u64,
+    pub last_access: u64,
+}
+
+/// The outcome of a thumbnail lookup.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ThumbnailLookup {
+    Ready(PathBuf),
+    /// Queued on the pool; announced by `thumbnail-ready`.
+    Pending,
+    /// The source could not be read or decoded.
+    Failed,
+}
+
+impl ThumbnailLookup {
+    pub fn ready(self) -> Option<PathBuf> {
+        match self {
+            ThumbnailLookup::Ready(path) => Some(path),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct CacheIndex {
+    /// Missing in indexes written before versioning, which read as version 1.
+    #[serde(default = "legacy_index_version")]
+    version: u32,
+    entries: HashMap<String, CacheEntry>,
+}
+
+impl Default for CacheIndex {
+    fn default() -> Self {
+        Self {
+            version: INDEX_VERSION,
+            entries: HashMap::new(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct KindUsage {
+    pub kind: ContentFolder,
+    pub bytes: u64,
+    pub files: usize,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheUsage {
+    pub path: PathBuf,
+    pub total_bytes: u64,
+    pub files: usize,
+    pub cap_bytes: u64,
+    pub by_kind: Vec<KindUsage>,
+}
+
+fn now_millis() -> u64 {
+    system_time_to_millis(Some(SystemTime::now())).unwrap_or_default()
+}
+
+fn legacy_index_version() -> u32 {
+    1
+}
+
+/// The key of a render: item, source, the source's size and mtime, and the output size.
+fn entry_key(
+    item: &Path,
+    source: &Path,
+    source_size: u64,
+    source_modified: Option<u64>,
+    size: ThumbnailSize,
+) -> String {
+    hash_str(&format!(
+        "{}|{}|{}|{}|{:?}",
+        normalize_path(item),
+        normalize_path(source),
+        source_size,
+        source_modified.unwrap_or_default(),
+        size
+    ))
+}
+
+/// Re-keys an older index and renames its files to match. Renders whose file is gone
+/// are dropped; they are rendered again on the next listing.
+fn migrate_index(root: &Path, index: CacheIndex) -> CacheIndex {
+    let mut migrated = CacheIndex::default();
+    for (_, mut entry) in index.entries {
+        let key = entry_key(
+            &entry.item,
+            &entry.source,
+            entry.source_size,
+            entry.source_modified,
+            entry.size,
+        );
+        let old = root.join(&entry.file);
+        let file = entry.file.with_file_name(format!(
+            "{}.{}",
+            key,
+            old.extension().and_then(OsStr::to_str).unwrap_or_default()
+        ));
+        let new = root.join(&file);
+        let _ = fs::rename(failure_marker(&old), failure_marker(&new));
+        if fs::rename(&old, &new).is_err() && !failure_marker(&new).is_file() {
+            continue;
+        }
+        entry.file = file;
+        migrated.entries.insert(key, entry);
+    }
+    migrated
+}
+
+/// Reads the index, migrating (and saving) one written with older keys. Callers hold
+/// `INDEX_LOCK`.
+fn load_index(root: &Path) -> CacheIndex {
+    let index: CacheIndex = fs::read(root.join(INDEX_FILE))
+        .ok()
+        .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+        .unwrap_or_default();
+    if index.version >= INDEX_VERSION {
+        return index;
+    }
+    let count = index.entries.len();
+    let index = migrate_index(root, index);
+    log::info!(
+        "[thumbnail_cache::load_index] Migrated {} of {} entries to stable keys",
+        index.entries.len(),
+        count
+    );
+    if let Err(e) = save_index(root, &index) {
+        log::warn!(
+            "[thumbnail_cache::load_index] Failed to save migrated index: {}",
+            e
+        );
+    }
+    index
+}
+
+/// Deletes an entry's render and failure marker. Returns whether a render was deleted.
+fn remove_entry_files(root: &Path, entry: &CacheEntry) -> bool {
+    let render = root.join(&entry.file);
+    let _ = fs::remove_file(failure_marker(&render));
+    fs::remove_file(render).is_ok()
+}
+
+fn save_index(root: &Path, index: &CacheIndex) -> CommandResult<()> {
+    fs::create_dir_all(root)?;
+    let json = serde_json::to_vec(index)
+        .map_err(|e| CommandError::Input(format!("Failed to serialize cache index: {}", e)))?;
+    let tmp = root.join(format!("{}.tmp", INDEX_FILE));
+    fs::write(&tmp, json)?;
+    fs::rename(&tmp, root.join(INDEX_FILE))?;
+    Ok(())
+}
+
+/// Drops least recently used entries until the cache fits in `cap_bytes`.
+fn evict_to_cap(root: &Path, index: &mut CacheIndex, cap_bytes: u64) -> usize {
+    for entry in index.entries.values_mut().filter(|e| e.bytes == 0) {
+        entry.bytes = fs::metadata(root.join(&entry.file)).map_or(0, |m| m.len());
+    }
+    let mut total: u64 = index.entries.values().map(|e| e.bytes).sum();
+    if total <= cap_bytes {
+        return 0;
+    }
+    let mut by_age: Vec<(String, u64, u64)> = index
+        .entries
+        .iter()
+        .map(|(key, e)| (key.clone(), e.last_access, e.bytes))
+        .collect();
+    by_age.sort_by_key(|(_, last_access, _)| *last_access);
+    let mut evicted = 0;
+    for (key, _, bytes) in by_age {
+        if total <= cap_bytes {
+            break;
+        }
+        if let Some(entry) = index.entries.remove(&key) {
+            remove_entry_files(root, &entry);
+            total -= bytes;
+            evicted += 1;
+        }
+    }
+    evicted
+}
+
+pub fn cache_root(app_handle: &AppHandle) -> Option<PathBuf> {
+    app_handle
+        .path()
+        .app_cache_dir()
+        .ok()
+        .map(|dir| dir.join("thumbnails"))
+}
+
+fn cap_bytes(app_handle: &AppHandle) -> u64 {
+    settings::current(app_handle).limits.thumbnail_cache_mb * 1024 * 1024
+}
+
+/// One listing's view of the cache for a single content kind. Lookups and inserts happen
+/// in memory; [`ThumbnailCache::finish`] prunes, evicts and writes the index.
+pub struct ThumbnailCache {
+    root: PathBuf,
+    kind: ContentFolder,
+    cap_bytes: u64,
+    entries: HashMap<String, CacheEntry>,
+    used: HashSet<String>,
+    /// Folders whose items were all looked up; only their unused entries are pruned.
+    scanned: Vec<PathBuf>,
+    /// Renders in the background when set, otherwise inline.
+    pool: Option<ThumbnailPool>,
+    /// Renders this cache queued, by item and size, so a logo can stand in if one fails.
+    pending: HashMap<(PathBuf, ThumbnailSize), PathBuf>,
+}
+
+impl ThumbnailCache {
+    pub fn open(root: PathBuf, kind: ContentFolder, cap_bytes: u64) -> Self {
+        let entries = {
+            let _guard = INDEX_LOCK.lock().unwrap();
+            load_index(&root)
+                .entries
+                .into_iter()
+                .filter(|(_, e)| e.kind == kind)
+                .collect()
+        };
+        Self {
+            root,
+            kind,
+            cap_bytes,
+            entries,
+            used: HashSet::new(),
+            scanned: Vec::new(),
+            pool: None,
+            pending: HashMap::new(),
+        }
+    }
+
+    pub fn for_app(app_handle: &AppHandle, kind: ContentFolder) -> Option<Self> {
+        let mut cache = Self::open(cache_root(app_handle)?, kind, cap_bytes(app_handle));
+        cache.pool = app_handle
+            .try_state::<ThumbnailPool>()
+            .map(|pool| pool.inner().clone());
+        Some(cache)
+    }
+
+    /// Records that every item in `folder` has been looked up.
+    pub fn mark_scanned(&mut self, folder: &Path) {
+        self.scanned.push(folder.to_path_buf());
+    }
+
+    fn kind_dir(&self) -> PathBuf {
+        self.root.join(self.kind.dir_name().to_lowercase())
+    }
+
+    /// The cached render of `source` for `item`. Missing or stale renders are queued, or
+    /// rendered inline without a pool.
+    pub fn thumbnail_for(
+        &mut self,
+        item: &Path,
+        source: &Path,
+        size: ThumbnailSize,
+    ) -> ThumbnailLookup {
+        let Ok(metadata) = fs::metadata(source) else {
+            return ThumbnailLookup::Failed;
+        };
+        let modified = system_time_to_millis(metadata.modified().ok());
+        self.lookup(item, source, (metadata.len(), modified), size, None)
+    }
+
+    /// The render of mod `mod_id`'s logo at `url`, for an item with no image of its own.
+    /// The logo is downloaded on the pool; without one, only logos already downloaded are
+    /// used. For an item whose own render is still queued, the logo is queued as its
+    /// fallback and only rendered if that render fails.
+    pub fn logo_for(
+        &mut self,
+        item: &Path,
+        mod_id: u64,
+        url: &str,
+        size: ThumbnailSize,
+    ) -> ThumbnailLookup {
+        let ext = url
+            .split(['?', '#'])
+            .next()
+            .and_then(|path| Path::new(path).extension())
+            .and_then(OsStr::to_str)
+            .map(str::to_lowercase)
+            .filter(|ext| THUMBNAIL_EXTS.contains(ext.as_str()))
+            .unwrap_or_else(|| "png".into());
+        // A new upload gets a new URL, so the URL's hash keeps the logo current.
+        let logo =
+            self.root
+                .join(LOGOS_DIR)
+                .join(format!("{}-{}.{}", mod_id, &hash_str(url)[..8], ext));
+        if !logo.is_file() && self.pool.is_none() {
+            return ThumbnailLookup::Failed;
+        }
+        self.lookup(item, &logo, (0, None), size, Some(url))
+    }
+
+    fn lookup(
+        &mut self,
+        item: &Path,
+        source: &Path,
+        (source_size, source_modified): (u64, Option<u64>),
+        size: ThumbnailSize,
+        fetch: Option<&str>,
+    ) -> ThumbnailLookup {
+        let key = entry_key(item, source, source_size, source_modified, size);
+        let file = PathBuf::from(self.kind.dir_name().to_lowercase())
+            .join(format!("{}.{}", key, OUTPUT_EXTENSION));
+        let cached = self.root.join(&file);
+        self.used.insert(key.clone());
+
+        if let Some(entry) = self.entries.get_mut(&key) {
+            entry.last_access = now_millis();
+            if cached.is_file() {
+                return ThumbnailLookup::Ready(cached);
+            }
+            if failure_marker(&cached).is_file() {
+                return ThumbnailLookup::Failed;
+            }
+        }
+
+        let mut entry = CacheEntry {
+            kind: self.kind,
+            item: item.to_path_buf(),
+            source: source.to_path_buf(),
+            source_size,
+            source_modified,
+            size,
+            file,
+            bytes: 0,
+            last_access: now_millis(),
+        };
+        let lookup = match &self.pool {
+            Some(pool) => {
+                let job = ThumbnailJob {
+                    kind: self.kind,
+                    item: item.to_path_buf(),
+                    source: source.to_path_buf(),
+                    dest: cached.clone(),
+                    size,
+                    fetch: fetch.map(str::to_string),
+                };
+                let pending = (item.to_path_buf(), size);
+                match self.pending.get(&pending).filter(|dest| **dest != cached) {
+                    Some(primary) if fetch.is_some() => pool.set_fallback(primary, job),
+                    _ => {
+                        pool.enqueue(job);
+                        self.pending.insert(pending, cached);
+                    }
+                }
+                ThumbnailLookup::Pending
+            }
+            None => match render_thumbnail(source, &cached, size) {
+                Ok(bytes) => {
+                    entry.bytes = bytes;
+                    ThumbnailLookup::Ready(cached)
+                }
+                Err(e) => {
+                    log::warn!("[thumbnail_cache::lookup] {}: {}", source.display(), e);
+                    ThumbnailLookup::Failed
+                }
+            },
+        };
+        self.entries.insert(key, entry);
+        lookup
+    }
+
+    /// Drops entries of scanned folders that no lookup used (removed items and outdated
+    /// sources) and stray files, then evicts to the size cap and saves the index. Entries
+    /// of folders this listing didn't scan are kept. Returns how many files were removed.
+    pub fn finish(mut self) -> CommandResult<usize> {
+        let mut removed = 0;
+        let unused: Vec<String> = self
+            .entries
+            .iter()
+            .filter(|(key, entry)| {
+                !self.used.contains(*key)
+                    && self.scanned.iter().any(|root| entry.item.starts_with(root))
+            })
+            .map(|(key, _)| key.clone())
+            .collect();
+        for key in unused {
+            if let Some(entry) = self.entries.remove(&key) {
+                if remove_entry_files(&self.root, &entry) {
+                    removed += 1;
+                }
+            }
+        }
+
+        let kind_dir = self.kind_dir();
+        let referenced: HashSet<PathBuf> = self
+            .entries
+            .values()
+            .flat_map(|e| {
+                let render = self.root.join(&e.file);
+                [failure_marker(&render), render]
+            })
+            .chain(
+                self.entries
+                    .values()
+                    .filter_map(|e| preview_file(&kind_dir, &e.source)),
+            )
+            .collect();
+        let files = fs::read_dir(&kind_dir)
+            .into_iter()
+            .chain(fs::read_dir(kind_dir.join("previews")))
+            .flatten();
+        for path in files.filter_map(Result::ok).map(|e| e.path()) {
+            // `.tmp` files are renders still being written.
+            let in_progress = path.extension().is_some_and(|ext| ext == "tmp");
+            if path.is_file()
+                && !in_progress
+                && !referenced.contains(&path)
+                && fs::remove_file(&path).is_ok()
+            {
+                removed += 1;
+            }
+        }
+
+        let _guard = INDEX_LOCK.lock().unwrap();
+        // Older versions cached straight into the root, outside any index.
+        if let Ok(files) = fs::read_dir(&self.root) {
+            for path in files.filter_map(Result::ok).map(|e| e.path()) {
+                let legacy = path.is_file()
+                    && path
+                        .extension()
+                        .and_then(OsStr::to_str)
+                        .is_some_and(|ext| THUMBNAIL_EXTS.contains(ext.to_lowercase().as_str()));
+                if legacy && fs::remove_file(&path).is_ok() {
+                    removed += 1;
+                }
+            }
+        }
+        let mut index = load_index(&self.root);
+        index.entries.retain(|_, e| e.kind != self.kind);
+        index.entries.extend(self.entries);
+        removed += evict_to_cap(&self.root, &mut index, self.cap_bytes);
+
+        // Logos are shared by every kind; drop the ones no entry points at.
+        let logos: HashSet<PathBuf> = index.entries.values().map(|e| e.source.clone()).collect();
+        if let Ok(files) = fs::read_dir(self.root.join(LOGOS_DIR)) {
+            for path in files.filter_map(Result::ok).map(|e| e.path()) {
+                let in_progress = path.extension().is_some_and(|ext| ext == "download");
+                if !in_progress && !logos.contains(&path) {
+                    let _ = fs::remove_file(&path);
+                }
+            }
+        }
+        save_index(&self.root, &index)?;
+        Ok(removed)
+    }
+}
+
+/// Removes cached thumbnails of a deleted item, or of anything inside a deleted folder.
+pub fn forget_item(app_handle: &AppHandle, item: &Path) {
+    let Some(root) = cache_root(app_handle) else {
+        return;
+    };
+    let _guard = INDEX_LOCK.lock().unwrap();
+    let mut index = load_index(&root);
+    let before = index.entries.len();
+    index.entries.retain(|_, e| {
+        let gone = e.item.starts_with(item);
+        if gone {
+            remove_entry_files(&root, e);
+        }
+        !gone
+    });
+    if index.entries.len() != before {
+        if let Err(e) = save_index(&root, &index) {
+            log::warn!("[thumbnail_cache::forget_item] {}", e);
+        }
+    }
+}
+
+fn usage_of(root: &Path, cap_bytes: u64) -> CacheUsage {
+    let index = load_index(root);
+    let by_kind: Vec<KindUsage> = ContentFolder::ALL
+        .iter()
+        .map(|kind| {
+            let entries = index.entries.values().filter(|e| e.kind == *kind);
+            KindUsage {
+                kind: *kind,
+                bytes: entries.clone().map(|e| e.bytes).sum(),
+                files: entries.count(),
+            }
+        })
+        .filter(|k| k.files > 0)
+        .collect();
+    CacheUsage {
+        path: root.to_path_buf(),
+        total_bytes: by_kind.iter().map(|k| k.bytes).sum(),
+        files: by_kind.iter().map(|k| k.files).sum(),
+        cap_bytes,
+        by_kind,
+    }
+}
+
+#[command]
+pub fn get_thumbnail_cache_usage(app_handle: AppHandle) -> CommandResult<CacheUsage> {
+    let root = cache_root(&app_handle).ok_or_else(|| {
+        CommandError::DirectoryResolution("App cache directory unavailable".into())
+    })?;
+    let _guard = INDEX_LOCK.lock().unwrap();
+    Ok(usage_of(&root, cap_bytes(&app_handle)))
+}
+
+/// Clears one kind's thumbnails, or the whole cache (including files from older
+/// versions that predate the index) when `kind` is omitted.
+#[command]
+pub fn clear_thumbnail_cache(
+    app_handle: AppHandle,
+    kind: Option<ContentFolder>,
+) -> CommandResult<CacheUsage> {
+    let root = cache_root(&app_handle).ok_or_else(|| {
+        CommandError::DirectoryResolution("App cache directory unavailable".into())
+    })?;
+    let _guard = INDEX_LOCK.lock().unwrap();
+    match kind {
+        Some(kind) => {
+            let mut index = load_index(&root);
+            index.entries.retain(|_, e| e.kind != kind);
+            let dir = root.join(kind.dir_name().to_lowercase());
+            if dir.is_dir() {
+                fs::remove_dir_all(&dir)?;
+            }
+            save_index(&root, &index)?;
+        }
+        None => {
+            if root.is_dir() {
+                fs::remove_dir_all(&root)?;
+            }
+        }
+    }
+    log::info!("[thumbnail_cache::clear] Cleared {:?}", kind);
+    Ok(usage_of(&root, cap_bytes(&app_handle)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("xlfm-thumbs-{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn write_png(path: &Path, width: u32, shade: u8) {
+        image::RgbImage::from_pixel(width, width / 2, image::Rgb([shade, 40, 90]))
+            .save(path)
+            .unwrap();
+    }
+
+    #[test]
+    fn renders_downscaled_thumbnails() {
+        let root = temp_dir();
+        let source = root.join("Park.jpg");
+        write_png(&root.join("Park.png"), 1920, 10);
+        fs::rename(root.join("Park.png"), &source).unwrap();
+
+        let mut cache = ThumbnailCache::open(root.join("cache"), ContentFolder::Maps, u64::MAX);
+        let small = cache
+            .thumbnail_for(&root.join("Park"), &source, ThumbnailSize::Small)
+            .ready()
+            .unwrap();
+        let large = cache
+            .thumbnail_for(&root.join("Park"), &source, ThumbnailSize::Large)
+            .ready()
+            .unwrap();
+
+        assert_eq!(small.extension().unwrap(), "jpg");
+        assert_eq!(image::image_dimensions(&small).unwrap(), (320, 180));
+        assert_eq!(image::image_dimensions(&large).unwrap(), (640, 360));
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn transparent_sources_render_as_jpeg() {
+        let root = temp_dir();
+        let source = root.join("Logo.png");
+        image::RgbaImage::from_pixel(400, 200, image::Rgba([255, 255, 255, 0]))
+            .save(&source)
+            .unwrap();
+
+        let mut cache = ThumbnailCache::open(root.join("cache"), ContentFolder::Maps, u64::MAX);
+        let small = cache
+            .thumbnail_for(&root.join("Logo"), &source, ThumbnailSize::Small)
+            .ready()
+            .unwrap();
+
+        assert_eq!(small.extension().unwrap(), "jpg");
+        let pixel = image::open(&small).unwrap().to_rgb8().get_pixel(160, 90).0;
+        assert!(pixel.iter().all(|&c| c < 8), "{:?}", pixel);
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn refreshes_when_the_source_changes() {
+        let root = temp_dir();
+        let source = root.join("Park.png");
+        write_png(&source, 400, 10);
+        let cache_root = root.join("cache");
+        let item = root.join("Park");
+
+        let mut cache = ThumbnailCache::open(cache_root.clone(), ContentFolder::Maps, u64::MAX);
+        let first = cache
+            .thumbnail_for(&item, &source, ThumbnailSize::Small)
+            .ready()
+            .unwrap();
+        cache.finish().unwrap();
+
+        write_png(&source, 800, 200);
+        let mut cache = ThumbnailCache::open(cache_root.clone(), ContentFolder::Maps, u64::MAX);
+        let second = cache
+            .thumbnail_for(&item, &source, ThumbnailSize::Small)
+            .ready()
+            .unwrap();
+        cache.mark_scanned(&root);
+        assert_eq!(cache.finish().unwrap(), 1);
+
+        assert_ne!(first, second);
+        assert!(!first.exists());
+        assert!(second.is_file());
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn remembers_sources_that_fail_to_decode() {
+        let root = temp_dir();
+        let source = root.join("Park.png");
+        fs::write(&source, "not an image").unwrap();
+        let cache_root = root.join("cache");
+        let item = root.join("Park");
+
+        let mut cache = ThumbnailCache::open(cache_root.clone(), ContentFolder::Maps, u64::MAX);
+        let lookup = cache.thumbnail_for(&item, &source, ThumbnailSize::Small);
+        assert_eq!(lookup, ThumbnailLookup::Failed);
+        cache.finish().unwrap();
+
+        let mut cache = ThumbnailCache::open(cache_root.clone(), ContentFolder::Maps, u64::MAX);
+        let lookup = cache.thumbnail_for(&item, &source, ThumbnailSize::Small);
+        assert_eq!(lookup, ThumbnailLookup::Failed);
+        assert_eq!(cache.finish().unwrap(), 0);
+
+        write_png(&source, 400, 10);
+        let mut cache = ThumbnailCache::open(cache_root, ContentFolder::Maps, u64::MAX);
+        let lookup = cache.thumbnail_for(&item, &source, ThumbnailSize::Small);
+        assert!(lookup.ready().is_some());
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn pins_entry_keys() {
+        let key = entry_key(
+            Path::new("C:\\Maps\\Park"),
+            Path::new("C:\\Maps\\Park\\Park.png"),
+            1024,
+            Some(1_700_000_000_000),
+            ThumbnailSize::Small,
+        );
+        assert_eq!(
+            key,
+            "647c5dd28f3bb0faa41b1b6977569a1448cb4f8f4dbc6394e66386afec396081"
+        );
+    }
+
+    #[test]
+    fn migrates_legacy_keys() {
+        let root = temp_dir();
+        let source = root.join("Park.png");
+        write_png(&source, 400, 10);
+        let item = root.join("Park");
+        let cache_root = root.join("cache");
+
+        let mut cache = ThumbnailCache::open(cache_root.clone(), ContentFolder::Maps, u64::MAX);
+        let render = cache
+            .thumbnail_for(&item, &source, ThumbnailSize::Small)
+            .ready()
+            .unwrap();
+        cache.finish().unwrap();
+
+        // Rewrite the index the way version 1 stored it: no version and a foreign key.
+        let legacy = render.with_file_name("9f3c2a71d0e4b685.jpg");
+        fs::rename(&render, &legacy).unwrap();
+        let mut index: serde_json::Value =
+            serde_json::from_slice(&fs::read(cache_root.join(INDEX_FILE)).unwrap()).unwrap();
+        let (_, mut entry) = index["entries"]
+            .as_object()
+            .unwrap()
+            .clone()
+            .into_iter()
+            .next()
+            .unwrap();
+        entry["file"] = serde_json::json!(PathBuf::from("maps").join("9f3c2a71d0e4b685.jpg"));
+        index = serde_json::json!({ "entries": { "9f3c2a71d0e4b685": entry } });
+        fs::write(cache_root.join(INDEX_FILE), index.to_string()).unwrap();
+
+        let mut cache = ThumbnailCache::open(cache_root.clone(), ContentFolder::Maps, u64::MAX);
+        let lookup = cache.thumbnail_for(&item, &source, ThumbnailSize::Small);
+        assert_eq!(lookup, ThumbnailLookup::Ready(render.clone()));
+        assert!(!legacy.exists());
+        assert_eq!(load_index(&cache_root).version, INDEX_VERSION);
+        fs::remove_dir_all(root).unwrap();
+    }
+
+    #[test]
+    fn evicts_least_recently_used_over_cap() {
+        let root = temp_dir();
+        let cache_root = root.join("cache");
+        for (i, name) in ["a", "b", "c"].into_iter().enumerate() {
+            write_png(&root.join(format!("{}.png", name)), 400, i as u8 * 80);
+        }
+
+        let mut cache = ThumbnailCache::open(cache_root.clone(), ContentFolder::Gear, u64::MAX);
+        let mut renders = Vec::new();
+        for name in ["a", "b", "c"] {
+            let item = root.join(name);
+            let source = root.join(format!("{}.png", name));
+            renders.push(
+                cache
+                    .thumbnail_for(&item, &source, ThumbnailSize::Small)
+                    .ready()
+                    .unwrap(),
+            );
+            std::thread::sleep(std::time::Duration::from_millis(5));
+        }
+        let size = |p: &PathBuf| fs::metadata(p).unwrap().len();
+        cache.cap_bytes = size(&renders[1]) + size(&renders[2]);
+        assert_eq!(cache.finish().unwrap(), 1);
+
+        assert!(!renders[0].exists());
+        assert!(renders[1].exists() && renders[2].exists());
+        assert_eq!(usage_of(&cache_root, 0).files, 2);
+        fs::remove_dir_all(root).unwrap();
+    }
+}
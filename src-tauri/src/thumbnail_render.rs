@@ -0,0 +1,354 @@
+// src-tauri/src/thumbnail_render.rs
+
+//! Decodes source images and writes downscaled thumbnails. Listings queue work on a small
+//! pool of background threads and return straight away; each finished thumbnail is
+//! announced with a `thumbnail-ready` event. Sources can also be asset bundles (see
+//! `bundle_preview`) or remote logos that are downloaded first.
+
+use crate::bundle_preview::{extract_preview, is_asset_bundle};
+use crate::content_folders::ContentFolder;
+use crate::utils::{hash_str, normalize_path, system_time_to_millis};
+use image::{
+    codecs::jpeg::JpegEncoder, imageops::FilterType, DynamicImage, ImageReader, Rgb, RgbImage,
+};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    fs::{self, File},
+    io::BufWriter,
+    path::{Path, PathBuf},
+    sync::{mpsc, Arc, Mutex},
+    thread,
+    time::Duration,
+};
+use tauri::{AppHandle, Emitter};
+
+pub const THUMBNAIL_READY_EVENT: &str = "thumbnail-ready";
+/// Every render is a JPEG, whatever the source format, so the cache file name is known
+/// before decoding.
+pub const OUTPUT_EXTENSION: &str = "jpg";
+pub const OUTPUT_MIME: &str = "image/jpeg";
+const JPEG_QUALITY: u8 = 82;
+
+/// Logo downloads give up after this, so a stalled server doesn't hold a worker.
+const FETCH_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Serializes bundle extraction. It is heavy, and the small and large render of one item
+/// would otherwise repeat it side by side.
+static SOURCE_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+/// One lock per logo being downloaded, so both sizes don't fetch it twice while other
+/// downloads go ahead.
+static FETCH_LOCKS: Lazy<Mutex<HashMap<PathBuf, Arc<Mutex<()>>>>> = Lazy::new(Mutex::default);
+static HTTP_CLIENT: Lazy<reqwest::Client> = Lazy::new(|| {
+    reqwest::Client::builder()
+        .timeout(FETCH_TIMEOUT)
+        .build()
+        .unwrap_or_default()
+});
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[serde(rename_all = "camelCase")]
+pub enum ThumbnailSize {
+    /// 320x180, for grid cards.
+    Small,
+    /// 640x360, for detail views and high-DPI grids.
+    Large,
+}
+
+impl ThumbnailSize {
+    pub fn dimensions(self) -> (u32, u32) {
+        match self {
+            ThumbnailSize::Small => (320, 180),
+            ThumbnailSize::Large => (640, 360),
+        }
+    }
+}
+
+/// Marks a source that could not be decoded, so later listings don't retry it until it
+/// changes.
+pub fn failure_marker(dest: &Path) -> PathBuf {
+    dest.with_extension("none")
+}
+
+/// Where the preview extracted from a bundle is kept, keyed by the bundle's path, size and
+/// mtime. `kind_dir` is the folder holding the renders.
+pub fn preview_file(kind_dir: &Path, source: &Path) -> Option<PathBuf> {
+    let metadata = fs::metadata(source).ok()?;
+    let key = hash_str(&format!(
+        "{}|{}|{}",
+        normalize_path(source),
+        metadata.len(),
+        system_time_to_millis(metadata.modified().ok()).unwrap_or_default()
+    ));
+    Some(kind_dir.join("previews").join(format!("{}.png", key)))
+}
+
+/// JPEG has no alpha channel, so transparent pixels are composited onto black instead
+/// of showing whatever color they happen to hold.
+fn flatten(image: &DynamicImage) -> RgbImage {
+    if !image.color().has_alpha() {
+        return image.to_rgb8();
+    }
+    let rgba = image.to_rgba8();
+    RgbImage::from_fn(rgba.width(), rgba.height(), |x, y| {
+        let [r, g, b, a] = rgba.get_pixel(x, y).0;
+        let blend = |c: u8| (u16::from(c) * u16::from(a) / 255) as u8;
+        Rgb([blend(r), blend(g), blend(b)])
+    })
+}
+
+fn decode_image(path: &Path) -> Result<DynamicImage, String> {
+    ImageReader::open(path)
+        .map_err(|e| e.to_string())?
+        .with_guessed_format()
+        .map_err(|e| e.to_string())?
+        .decode()
+        .map_err(|e| e.to_string())
+}
+
+/// Decodes an image file, or a bundle's embedded preview through its extracted copy.
+fn load_source(source: &Path, dest: &Path) -> Result<DynamicImage, String> {
+    if !is_asset_bundle(source) {
+        return decode_image(source);
+    }
+    let _guard = SOURCE_LOCK.lock().unwrap();
+    let memo = dest.parent().and_then(|dir| preview_file(dir, source));
+    if let Some(memo) = memo.as_deref().filter(|m| m.is_file()) {
+        return decode_image(memo);
+    }
+    let image = extract_preview(source)?;
+    if let Some(memo) = memo {
+        let saved = memo
+            .parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .map_err(|e| e.to_string())
+            .and_then(|_| image.save(&memo).map_err(|e| e.to_string()));
+        if let Err(e) = saved {
+            log::warn!(
+                "[thumbnail_render::load_source] Failed to keep preview: {}",
+                e
+            );
+        }
+    }
+    Ok(image)
+}
+
+/// Downloads `url` to `dest` unless an earlier job already did.
+fn fetch_source(url: &str, dest: &Path) -> Result<(), String> {
+    let lock = Arc::clone(
+        FETCH_LOCKS
+            .lock()
+            .unwrap()
+            .entry(dest.to_path_buf())
+            .or_default(),
+    );
+    let result = {
+        let _guard = lock.lock().unwrap();
+        download(url, dest)
+    };
+    FETCH_LOCKS.lock().unwrap().remove(dest);
+    result
+}
+
+fn download(url: &str, dest: &Path) -> Result<(), String> {
+    if dest.is_file() {
+        return Ok(());
+    }
+    let bytes = tauri::async_runtime::block_on(async {
+        HTTP_CLIENT
+            .get(url)
+            .send()
+            .await?
+            .error_for_status()?
+            .bytes()
+            .await
+    })
+    .map_err(|e| format!("Failed to download {}: {}", url, e))?;
+    if let Some(parent) = dest.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let tmp = dest.with_extension("download");
+    fs::write(&tmp, &bytes).map_err(|e| e.to_string())?;
+    fs::rename(&tmp, dest).map_err(|e| e.to_string())
+}
+
+/// Decodes `source`, crops it to the size's 16:9 box and writes it to `dest` through a
+/// temp file. Returns the written size in bytes; a source that fails to decode leaves a
+/// [`failure_marker`] behind.
+pub fn render_thumbnail(source: &Path, dest: &Path, size: ThumbnailSize) -> Result<u64, String> {
+    let image = match load_source(source, dest) {
+        Ok(image) => image,
+        Err(e) => {
+            if let Some(parent) = dest.parent() {
+                let _ = fs::create_dir_all(parent);
+            }
+            let _ = fs::write(failure_marker(dest), &e);
+            return Err(e);
+        }
+    };
+    let (width, height) = size.dimensions();
+    let resized = image.resize_to_fill(width, height, FilterType::Triangle);
+
+    if let Some(parent) = dest.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let tmp = dest.with_extension("tmp");
+    {
+        let mut writer = BufWriter::new(File::create(&tmp).map_err(|e| e.to_string())?);
+        let written = DynamicImage::ImageRgb8(flatten(&resized))
+            .write_with_encoder(JpegEncoder::new_with_quality(&mut writer, JPEG_QUALITY));
+        if let Err(e) = written {
+            drop(writer);
+            let _ = fs::remove_file(&tmp);
+            return Err(e.to_string());
+        }
+    }
+    fs::rename(&tmp, dest).map_err(|e| e.to_string())?;
+    fs::metadata(dest)
+        .map(|m| m.len())
+        .map_err(|e| e.to_string())
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ThumbnailReady {
+    pub kind: ContentFolder,
+    /// The listed item (map folder, mod folder, …) the thumbnail belongs to.
+    pub item: PathBuf,
+    pub size: ThumbnailSize,
+    /// `None` when the source could not be decoded.
+    pub thumbnail_path: Option<PathBuf>,
+    pub mime_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+pub struct ThumbnailJob {
+    pub kind: ContentFolder,
+    pub item: PathBuf,
+    pub source: PathBuf,
+    pub dest: PathBuf,
+    pub size: ThumbnailSize,
+    /// Downloaded to `source` first when it is missing.
+    pub fetch: Option<String>,
+}
+
+/// Handle to the render threads. Cheap to clone; jobs for a destination already in the
+/// queue are dropped.
+#[derive(Clone)]
+pub struct ThumbnailPool {
+    sender: mpsc::Sender<ThumbnailJob>,
+    /// Queued destinations, each with the job to run instead if it fails.
+    queued: Arc<Mutex<HashMap<PathBuf, Option<ThumbnailJob>>>>,
+}
+
+impl ThumbnailPool {
+    pub fn start(app_handle: AppHandle, workers: usize) -> Self {
+        let (sender, receiver) = mpsc::channel::<ThumbnailJob>();
+        let receiver = Arc::new(Mutex::new(receiver));
+        let queued: Arc<Mutex<HashMap<PathBuf, Option<ThumbnailJob>>>> = Arc::default();
+        for i in 0..workers.max(1) {
+            let receiver = Arc::clone(&receiver);
+            let queued = Arc::clone(&queued);
+            let app_handle = app_handle.clone();
+            let spawned = thread::Builder::new()
+                .name(format!("thumbnail-worker-{}", i))
+                .spawn(move || loop {
+                    let mut next = match receiver.lock().unwrap().recv() {
+                        Ok(job) => Some(job),
+                        Err(_) => break,
+                    };
+                    while let Some(job) = next.take() {
+                        let result = match &job.fetch {
+                            Some(url) => fetch_source(url, &job.source),
+                            None => Ok(()),
+                        }
+                        .and_then(|_| render_thumbnail(&job.source, &job.dest, job.size));
+                        let fallback = {
+                            let mut queued = queued.lock().unwrap();
+                            let fallback = queued.remove(&job.dest).flatten();
+                            match (&result, fallback) {
+                                (Err(_), Some(fallback)) => {
+                                    queued.entry(fallback.dest.clone()).or_insert(None);
+                                    Some(fallback)
+                                }
+                                _ => None,
+                            }
+                        };
+                        if let (Err(e), Some(_)) = (&result, &fallback) {
+                            log::info!(
+                                "[thumbnail_render::worker] {}: {}; using the fallback",
+                                job.source.display(),
+                                e
+                            );
+                            next = fallback;
+                            continue;
+                        }
+                        let ready = match result {
+                            Ok(_) => ThumbnailReady {
+                                kind: job.kind,
+                                item: job.item,
+                                size: job.size,
+                                mime_type: Some(OUTPUT_MIME.to_string()),
+                                thumbnail_path: Some(job.dest),
+                                error: None,
+                            },
+                            Err(e) => {
+                                log::warn!(
+                                    "[thumbnail_render::worker] {}: {}",
+                                    job.source.display(),
+                                    e
+                                );
+                                ThumbnailReady {
+                                    kind: job.kind,
+                                    item: job.item,
+                                    size: job.size,
+                                    thumbnail_path: None,
+                                    mime_type: None,
+                                    error: Some(e),
+                                }
+                            }
+                        };
+                        if let Err(e) = app_handle.emit(THUMBNAIL_READY_EVENT, &ready) {
+                            log::warn!("[thumbnail_render::worker] emit failed: {}", e);
+                        }
+                    }
+                });
+            if let Err(e) = spawned {
+                log::error!("[thumbnail_render::start] Failed to spawn worker: {}", e);
+            }
+        }
+        Self { sender, queued }
+    }
+
+    pub fn enqueue(&self, job: ThumbnailJob) {
+        {
+            let mut queued = self.queued.lock().unwrap();
+            if queued.contains_key(&job.dest) {
+                return;
+            }
+            queued.insert(job.dest.clone(), None);
+        }
+        let dest = job.dest.clone();
+        if self.sender.send(job).is_err() {
+            log::warn!("[thumbnail_render::enqueue] Pool stopped, dropping job");
+            self.queued.lock().unwrap().remove(&dest);
+        }
+    }
+
+    /// Runs `fallback` in place of the job rendering `dest` if that one fails, or right
+    /// away when it already has.
+    pub fn set_fallback(&self, dest: &Path, fallback: ThumbnailJob) {
+        {
+            let mut queued = self.queued.lock().unwrap();
+            if let Some(slot) = queued.get_mut(dest) {
+                *slot = Some(fallback);
+                return;
+            }
+        }
+        if failure_marker(dest).is_file() {
+            self.enqueue(fallback);
+        }
+    }
+}
@@ -0,0 +1,296 @@
+// src-tauri/src/umm.rs
+
+//! Unity Mod Manager support: every Skater XL mod is a folder in Mods with an `Info.json`,
+//! and UMM keeps each mod's enabled flag in its `Params.xml`.
+
+use crate::models::{ManifestStatus, ModInfo};
+use crate::path_finder::locate_game_installation;
+use crate::settings;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+};
+use tauri::AppHandle;
+
+pub const INFO_FILE: &str = "Info.json";
+pub const PARAMS_FILE: &str = "Params.xml";
+
+/// Finds `Info.json` in a mod folder, ignoring case (`info.json` is common on Linux).
+pub fn info_path(mod_dir: &Path) -> Option<PathBuf> {
+    let exact = mod_dir.join(INFO_FILE);
+    if exact.is_file() {
+        return Some(exact);
+    }
+    fs::read_dir(mod_dir)
+        .ok()?
+        .filter_map(Result::ok)
+        .find(|e| {
+            e.file_name()
+                .to_string_lossy()
+                .eq_ignore_ascii_case(INFO_FILE)
+        })
+        .map(|e| e.path())
+}
+
+/// Parses the manifest text. UMM writes it with a UTF-8 BOM, which serde rejects.
+pub fn parse_mod_info(contents: &str) -> Result<ModInfo, String> {
+    let info: ModInfo =
+        serde_json::from_str(contents.trim_start_matches('\u{feff}')).map_err(|e| e.to_string())?;
+    if info.id.trim().is_empty() {
+        return Err("Id is empty".into());
+    }
+    Ok(info)
+}
+
+/// Reads a mod folder's manifest and reports whether it was usable.
+pub fn read_mod_info(mod_dir: &Path) -> (Option<ModInfo>, ManifestStatus) {
+    let Some(path) = info_path(mod_dir) else {
+        return (None, ManifestStatus::Missing);
+    };
+    let result = fs::read_to_string(&path)
+        .map_err(|e| e.to_string())
+        .and_then(|contents| parse_mod_info(&contents));
+    match result {
+        Ok(info) => (Some(info), ManifestStatus::Valid),
+        Err(message) => {
+            log::warn!(
+                "[umm::read_mod_info] Malformed {}: {}",
+                path.display(),
+                message
+            );
+            (None, ManifestStatus::Malformed { message })
+        }
+    }
+}
+
+/// The game's install folder: the configured one, else the Steam install.
+pub fn game_install_dir(app_handle: &AppHandle) -> Option<PathBuf> {
+    settings::current(app_handle)
+        .game_install_path
+        .filter(|p| p.is_dir())
+        .or_else(|| locate_game_installation().install_dir)
+}
+
+/// UMM's settings file, which holds the per-mod enabled flags.
+pub fn params_path(game_dir: &Path) -> Option<PathBuf> {
+    [
+        game_dir
+            .join("SkaterXL_Data")
+            .join("Managed")
+            .join("UnityModManager")
+            .join(PARAMS_FILE),
+        game_dir.join("UnityModManager").join(PARAMS_FILE),
+    ]
+    .into_iter()
+    .find(|p| p.is_file())
+}
+
+fn xml_escape(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('"', "&quot;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// Decodes the entities an XML serializer may write in an attribute value.
+fn xml_unescape(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    let mut rest = value;
+    while let Some(amp) = rest.find('&') {
+        out.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+        let decoded = rest.find(';').and_then(|end| {
+            let c = match &rest[1..end] {
+                "amp" => '&',
+                "quot" => '"',
+                "apos" => '\'',
+                "lt" => '<',
+                "gt" => '>',
+                entity => {
+                    let code = match entity.strip_prefix("#x").or(entity.strip_prefix("#X")) {
+                        Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                        None => entity.strip_prefix('#')?.parse().ok()?,
+                    };
+                    char::from_u32(code)?
+                }
+            };
+            Some((c, end))
+        });
+        match decoded {
+            Some((c, end)) => {
+                out.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// The unescaped `Id` of a `<Mod>` element.
+fn mod_id(element: &str) -> Option<String> {
+    Some(xml_unescape(ID_ATTR.captures(element)?.get(1)?.as_str()))
+}
+
+static MOD_ELEMENT: Lazy<Regex> = Lazy::new(|| Regex::new(r"<Mod\b[^>]*?/?>").unwrap());
+static ID_ATTR: Lazy<Regex> = Lazy::new(|| Regex::new(r#"\bId="([^"]*)""#).unwrap());
+static ENABLED_ATTR: Lazy<Regex> = Lazy::new(|| Regex::new(r#"\bEnabled="([^"]*)""#).unwrap());
+
+/// Reads `<ModParams><Mod Id=".." Enabled=".." /></ModParams>`. Mods UMM has never seen
+/// are not listed and count as enabled.
+pub fn read_mod_states(params_xml: &str) -> HashMap<String, bool> {
+    MOD_ELEMENT
+        .find_iter(params_xml)
+        .filter_map(|element| {
+            let element = element.as_str();
+            let id = mod_id(element)?;
+            let enabled = ENABLED_ATTR
+                .captures(element)
+                .and_then(|c| c.get(1))
+                .is_none_or(|v| !v.as_str().eq_ignore_ascii_case("false"));
+            Some((id, enabled))
+        })
+        .collect()
+}
+
+/// Sets one mod's `Enabled` flag, adding its `<Mod>` element (and `<ModParams>`) if needed.
+/// Everything else in the file is left byte for byte as it was.
+pub fn set_mod_state(params_xml: &str, id: &str, enabled: bool) -> Result<String, String> {
+    let flag = if enabled { "true" } else { "false" };
+    let existing = MOD_ELEMENT
+        .find_iter(params_xml)
+        .find(|element| mod_id(element.as_str()).is_some_and(|v| v == id));
+    if let Some(element) = existing {
+        let old = element.as_str();
+        let new = if ENABLED_ATTR.is_match(old) {
+            ENABLED_ATTR
+                .replace(old, format!(r#"Enabled="{}""#, flag))
+                .into_owned()
+        } else {
+            old.replacen("<Mod", &format!(r#"<Mod Enabled="{}""#, flag), 1)
+        };
+        return Ok(format!(
+            "{}{}{}",
+            &params_xml[..element.start()],
+            new,
+            &params_xml[element.end()..]
+        ));
+    }
+
+    let element = format!(r#"<Mod Id="{}" Enabled="{}" />"#, xml_escape(id), flag);
+    if let Some(pos) = params_xml.find("</ModParams>") {
+        Ok(format!(
+            "{}  {}\n  {}",
+            &params_xml[..pos],
+            element,
+            &params_xml[pos..]
+        ))
+    } else if let Some(empty) = ["<ModParams />", "<ModParams/>"]
+        .into_iter()
+        .find(|tag| params_xml.contains(tag))
+    {
+        let block = format!("<ModParams>\n    {}\n  </ModParams>", element);
+        Ok(params_xml.replacen(empty, &block, 1))
+    } else if let Some(pos) = params_xml.rfind("</Param>") {
+        Ok(format!(
+            "{}  <ModParams>\n    {}\n  </ModParams>\n{}",
+            &params_xml[..pos],
+            element,
+            &params_xml[pos..]
+        ))
+    } else {
+        Err("Params.xml has no <Param> root element".into())
+    }
+}
+
+/// Writes Params.xml through a temp file so a crash never leaves UMM with half a config.
+pub fn write_params(path: &Path, contents: &str) -> std::io::Result<()> {
+    let tmp = path.with_extension("xml.xlfm-tmp");
+    fs::write(&tmp, contents)?;
+    fs::rename(&tmp, path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PARAMS: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+<Param xmlns:xsd="http://www.w3.org/2001/XMLSchema">
+  <ShortcutKeyId>0</ShortcutKeyId>
+  <ModParams>
+    <Mod Id="XLGraphics" Enabled="true" />
+    <Mod Id="XLShredMenu" Enabled="false" />
+    <Mod Id="Old" />
+    <Mod Id="Tom &amp; Jerry&#39;s &lt;&quot;Mod&quot;&gt;" Enabled="false" />
+  </ModParams>
+</Param>
+"#;
+
+    #[test]
+    fn reads_flags_and_unescapes_ids() {
+        let states = read_mod_states(PARAMS);
+        assert_eq!(states.len(), 4);
+        assert!(states["XLGraphics"]);
+        assert!(!states["XLShredMenu"]);
+        assert!(states["Old"]);
+        assert!(!states[r#"Tom & Jerry's <"Mod">"#]);
+        assert!(read_mod_states("<Param><ModParams/></Param>").is_empty());
+    }
+
+    #[test]
+    fn flips_an_existing_flag_in_place() {
+        let updated = set_mod_state(PARAMS, "XLShredMenu", true).unwrap();
+        assert_eq!(
+            updated,
+            PARAMS.replace(
+                r#"<Mod Id="XLShredMenu" Enabled="false" />"#,
+                r#"<Mod Id="XLShredMenu" Enabled="true" />"#
+            )
+        );
+        let escaped = set_mod_state(PARAMS, r#"Tom & Jerry's <"Mod">"#, true).unwrap();
+        assert!(read_mod_states(&escaped)[r#"Tom & Jerry's <"Mod">"#]);
+        assert_eq!(read_mod_states(&escaped).len(), 4);
+    }
+
+    #[test]
+    fn adds_a_missing_enabled_attribute() {
+        let updated = set_mod_state(PARAMS, "Old", false).unwrap();
+        assert!(updated.contains(r#"<Mod Enabled="false" Id="Old" />"#));
+        assert!(!read_mod_states(&updated)["Old"]);
+    }
+
+    #[test]
+    fn adds_a_new_mod_element() {
+        let updated = set_mod_state(PARAMS, "A&B", false).unwrap();
+        assert!(updated.contains("    <Mod Id=\"A&amp;B\" Enabled=\"false\" />\n  </ModParams>"));
+        let states = read_mod_states(&updated);
+        assert_eq!((states.len(), states["A&B"]), (5, false));
+    }
+
+    #[test]
+    fn fills_a_self_closing_mod_params() {
+        let xml = "<Param>\n  <ModParams />\n</Param>\n";
+        let updated = set_mod_state(xml, "XLGraphics", false).unwrap();
+        assert_eq!(
+            updated,
+            "<Param>\n  <ModParams>\n    <Mod Id=\"XLGraphics\" Enabled=\"false\" />\n  </ModParams>\n</Param>\n"
+        );
+    }
+
+    #[test]
+    fn adds_mod_params_when_missing() {
+        let xml = "<Param>\n  <ShortcutKeyId>0</ShortcutKeyId>\n</Param>\n";
+        let updated = set_mod_state(xml, "XLGraphics", true).unwrap();
+        assert!(read_mod_states(&updated)["XLGraphics"]);
+        assert!(updated.ends_with("  </ModParams>\n</Param>\n"));
+        assert!(set_mod_state("<Settings />", "XLGraphics", true).is_err());
+    }
+}
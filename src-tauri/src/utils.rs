@@ -2,22 +2,27 @@
 
 //! Contains shared utility functions, constants, and helper logic.
 
-use directories::UserDirs;
 use once_cell::sync::Lazy;
-use std::collections::hash_map::DefaultHasher;
-use std::hash::{Hash, Hasher};
+use sha2::{Digest, Sha256};
 use std::{
     collections::HashSet,
-    path::{Path, PathBuf},
+    path::Path,
     time::{SystemTime, UNIX_EPOCH},
 };
 use walkdir::WalkDir;
 
-/// Generates a simple hash string from a Path, useful for cache keys.
-pub fn hash_path(path: &Path) -> String {
-    let mut hasher = DefaultHasher::new();
-    path.to_string_lossy().hash(&mut hasher);
-    format!("{:x}", hasher.finish())
+/// A path as text with `/` separators, the form hashed into cache keys.
+pub fn normalize_path(path: &Path) -> String {
+    path.to_string_lossy().replace('\\', "/")
+}
+
+/// Lowercase hex SHA-256 of `value`. Used for cache file names, so the output must never
+/// change between releases.
+pub fn hash_str(value: &str) -> String {
+    Sha256::digest(value.as_bytes())
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
 }
 
 /// Set of file extensions considered for generating thumbnails in list_local_maps.
@@ -77,16 +82,6 @@ pub fn system_time_to_millis(time: Option<SystemTime>) -> Option<u64> {
     })
 }
 
-/// Resolves a path relative to the user's Documents directory.
-pub fn resolve_document_path(relative_path: &str) -> Result<PathBuf, String> {
-    let user_dirs =
-        UserDirs::new().ok_or_else(|| "Could not determine user directories".to_string())?;
-    let documents = user_dirs
-        .document_dir()
-        .ok_or_else(|| "Could not determine documents directory".to_string())?;
-    Ok(documents.join(relative_path))
-}
-
 /// Gets a basic image MIME type from a file extension (lowercase).
 pub fn get_mime_type_from_extension(extension: &str) -> Option<String> {
     match extension.to_lowercase().as_str() {
@@ -98,3 +93,21 @@ pub fn get_mime_type_from_extension(extension: &str) -> Option<String> {
         _ => None,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Cache file names depend on these exact outputs.
+    #[test]
+    fn hashes_are_pinned() {
+        assert_eq!(
+            hash_str(""),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+        assert_eq!(
+            hash_str("abc"),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+}
@@ -1,5 +1,6 @@
 // src-tauri/src/watcher.rs
 
+use crate::content_folders::is_write_probe;
 use crate::error::{CommandError, CommandResult};
 use crate::state::WatcherCommand;
 
@@ -132,6 +133,9 @@ pub fn run_watcher(app: AppHandle, mut rx: Receiver<WatcherCommand>) {
                   _ => None,
                 } {
                   for p in event.paths {
+                    if is_write_probe(&p) {
+                      continue;
+                    }
                     debounce.insert(p, kind.to_string());
                     last = Instant::now();
                   }
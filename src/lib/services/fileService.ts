@@ -1,15 +1,28 @@
 import { invoke } from '@tauri-apps/api/core'
-import type { DirectoryListingResult } from '$lib/types/fsTypes'
+import type {
+  ContentFolder,
+  DirectoryListingResult,
+  MapMetadata,
+} from '$lib/types/fsTypes'
+import type { Mod } from '$lib/types/modioTypes'
 import { normalizePath } from './pathService'
 
-export const loadLocalMaps = async (absolutePath: string) =>
+/** `folder` is an absolute path or a logical name such as `'maps'`. */
+export const loadLocalMaps = async (folder: string) =>
   invoke<DirectoryListingResult>('list_local_maps', {
-    relativeMapsPath: normalizePath(absolutePath),
+    folder: normalizePath(folder),
   })
 
-export const loadLocalMods = async (absolutePath: string) =>
+export const loadLocalMods = async (folder: string) =>
   invoke<DirectoryListingResult>('list_local_mods', {
-    relativeMapsPath: normalizePath(absolutePath),
+    folder: normalizePath(folder),
+  })
+
+/** Lists any content folder; omit `folder` to use the configured one. */
+export const loadLocalContent = async (kind: ContentFolder, folder?: string) =>
+  invoke<DirectoryListingResult>('list_local_content', {
+    kind,
+    folder: folder ? normalizePath(folder) : null,
   })
 
 export const loadDirectoryEntries = async (absolutePath: string) =>
@@ -44,8 +57,31 @@ export const deleteEntry = async (absolutePath: string): Promise<void> =>
 export const downloadAndInstall = async (
   url: string,
   destinationSubfolder: string,
+  modId?: number,
 ): Promise<void> =>
   invoke('download_and_install', {
     url,
     destinationSubfolder: normalizePath(destinationSubfolder),
+    modId: modId ?? null,
   })
+
+export const syncModioCatalog = async (mods: Mod[]): Promise<void> =>
+  invoke('sync_modio_catalog', { mods })
+
+export const setMapMetadata = async (
+  mapPath: string,
+  metadata: MapMetadata,
+): Promise<void> =>
+  invoke('set_map_metadata', { mapPath: normalizePath(mapPath), metadata })
+
+export const setMapRating = async (
+  mapPath: string,
+  rating: number | null,
+): Promise<void> =>
+  invoke('set_map_rating', { mapPath: normalizePath(mapPath), rating })
+
+export const enableMap = async (mapPath: string): Promise<string> =>
+  invoke<string>('enable_map', { mapPath: normalizePath(mapPath) })
+
+export const disableMap = async (mapPath: string): Promise<string> =>
+  invoke<string>('disable_map', { mapPath: normalizePath(mapPath) })
@@ -0,0 +1,24 @@
+import { invoke } from '@tauri-apps/api/core'
+import type {
+  LibraryMapRef,
+  MaterializeResult,
+} from '$lib/types/profileTypes'
+import { normalizePath } from './pathService'
+
+export const listMapLibraryRoots = async () =>
+  invoke<string[]>('list_map_library_roots')
+
+export const addMapLibraryRoot = async (path: string) =>
+  invoke<string[]>('add_map_library_root', { path: normalizePath(path) })
+
+export const removeMapLibraryRoot = async (path: string) =>
+  invoke<string[]>('remove_map_library_root', { path })
+
+export const materializeLibraryMaps = async (
+  folder: string,
+  maps: LibraryMapRef[],
+) =>
+  invoke<MaterializeResult>('materialize_library_maps', {
+    folder: normalizePath(folder),
+    maps,
+  })
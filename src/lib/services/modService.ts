@@ -0,0 +1,42 @@
+import { invoke } from '@tauri-apps/api/core'
+import type {
+  CompatibilityReport,
+  DllConflictReport,
+  LoaderKind,
+  LoaderStatus,
+  ModToggleResult,
+} from '$lib/types/modTypes'
+import { normalizePath } from './pathService'
+
+export const checkModCompatibility = async (folder?: string) =>
+  invoke<CompatibilityReport>('check_mod_compatibility', {
+    folder: folder ? normalizePath(folder) : null,
+  })
+
+export const scanDllConflicts = async (folder?: string) =>
+  invoke<DllConflictReport>('scan_dll_conflicts', {
+    folder: folder ? normalizePath(folder) : null,
+  })
+
+export const enableMod = async (modPath: string) =>
+  invoke<ModToggleResult>('enable_mod', { modPath })
+
+export const disableMod = async (modPath: string) =>
+  invoke<ModToggleResult>('disable_mod', { modPath })
+
+export const detectModLoaders = async () =>
+  invoke<LoaderStatus[]>('detect_mod_loaders')
+
+export const installModLoader = async (
+  kind: LoaderKind,
+  archivePath: string,
+  version?: string,
+) =>
+  invoke<LoaderStatus>('install_mod_loader', {
+    kind,
+    archivePath: normalizePath(archivePath),
+    version: version ?? null,
+  })
+
+export const uninstallModLoader = async (kind: LoaderKind) =>
+  invoke<LoaderStatus>('uninstall_mod_loader', { kind })
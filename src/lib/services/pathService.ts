@@ -1,6 +1,11 @@
 import { invoke } from '@tauri-apps/api/core';
 import { join, documentDir } from '@tauri-apps/api/path';
 import { handleError } from '$lib/utils/errorHandler';
+import type {
+  DiscoveryReport,
+  GameInstallation,
+  UserDataCandidate,
+} from '$lib/types/fsTypes';
 
 export const normalizePath = (path: string): string => path.replace(/\\/g, '/');
 
@@ -20,6 +25,42 @@ export async function findSkaterXlPath(): Promise<string | null> {
   return null;
 }
 
+export async function findSkaterXlCandidates(
+  customPrefixes: string[] = [],
+): Promise<UserDataCandidate[]> {
+  try {
+    return await invoke<UserDataCandidate[]>('find_skaterxl_user_data_candidates', {
+      customPrefixes,
+    });
+  } catch (err) {
+    handleError(err, 'Finding Skater XL data folders');
+  }
+  return [];
+}
+
+/** Everything auto-detection checked; shown on the settings page when it fails. */
+export async function discoverSkaterXlPaths(
+  customPrefixes: string[] = [],
+): Promise<DiscoveryReport | null> {
+  try {
+    return await invoke<DiscoveryReport>('discover_skaterxl_paths', {
+      customPrefixes,
+    });
+  } catch (err) {
+    handleError(err, 'Running Skater XL path discovery');
+  }
+  return null;
+}
+
+export async function findGameInstallation(): Promise<GameInstallation | null> {
+  try {
+    return await invoke<GameInstallation>('find_game_installation');
+  } catch (err) {
+    handleError(err, 'Finding Skater XL install');
+  }
+  return null;
+}
+
 export function getFileName(path: string | null | undefined): string {
   if (!path) return '';
   const normalized = normalizePath(path);
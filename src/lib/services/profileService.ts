@@ -0,0 +1,29 @@
+import { invoke } from '@tauri-apps/api/core'
+import type {
+  MapProfilesConfig,
+  ProfileActivationResult,
+} from '$lib/types/profileTypes'
+import { normalizePath } from './pathService'
+
+export const listMapProfiles = async () =>
+  invoke<MapProfilesConfig>('list_map_profiles')
+
+export const setMapLibrary = async (libraryPath: string): Promise<void> =>
+  invoke('set_map_library', { libraryPath: normalizePath(libraryPath) })
+
+export const saveMapProfile = async (
+  name: string,
+  maps: string[],
+): Promise<void> => invoke('save_map_profile', { name, maps })
+
+export const deleteMapProfile = async (name: string): Promise<void> =>
+  invoke('delete_map_profile', { name })
+
+export const activateMapProfile = async (name: string, targetLink: string) =>
+  invoke<ProfileActivationResult>('activate_map_profile', {
+    name,
+    targetLink: normalizePath(targetLink),
+  })
+
+export const deactivateMapProfile = async (targetLink: string): Promise<void> =>
+  invoke('deactivate_map_profile', { targetLink: normalizePath(targetLink) })
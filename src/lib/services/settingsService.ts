@@ -0,0 +1,11 @@
+import { invoke } from '@tauri-apps/api/core'
+import { listen } from '@tauri-apps/api/event'
+import type { Settings, SettingsPatch } from '$lib/types/settingsTypes'
+
+export const getSettings = async () => invoke<Settings>('get_settings')
+
+export const updateSettings = async (patch: SettingsPatch) =>
+  invoke<Settings>('update_settings', { patch })
+
+export const onSettingsChanged = (callback: (settings: Settings) => void) =>
+  listen<Settings>('settings-changed', (evt) => callback(evt.payload))
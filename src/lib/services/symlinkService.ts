@@ -1,27 +1,124 @@
 import { invoke } from '@tauri-apps/api/core'
-import { documentDir, join } from '@tauri-apps/api/path'
-import { mapsDirectory, explorerDirectory } from '$lib/stores/globalPathsStore'
+import {
+  mapsDirectory,
+  modsDirectory,
+  explorerDirectory,
+} from '$lib/stores/globalPathsStore'
 import { setPath } from '$lib/stores/explorerStore'
 import { get } from 'svelte/store'
+import type {
+  BackupEntry,
+  BackupMergeResult,
+  ContentFolder,
+  ContentFolderStatus,
+  LinkHealth,
+  RelocateMode,
+  RelocationResult,
+  RepairAction,
+} from '$lib/types/fsTypes'
 
-export async function updateMapsSymlink(newFolder: string) {
-  const docs = await documentDir()
-  if (!docs) throw new Error('Could not determine Documents directory.')
-  const targetLink = await join(docs, 'SkaterXL', 'Maps')
-
-  await invoke('create_maps_symlink', {
-    newFolder,
-    targetLink,
-  })
-
+async function refreshAfterLinkChange(kind: ContentFolder, newFolder: string) {
   // 1. Update the store
-  mapsDirectory.set(newFolder)
+  if (kind === 'maps') mapsDirectory.set(newFolder)
+  if (kind === 'mods') modsDirectory.set(newFolder)
 
   // 2. Immediately tell explorer to re-watch the folder
   const current = get(explorerDirectory)
   if (current) {
     await setPath(current)
   }
+}
+
+export const listContentFolders = async () =>
+  invoke<ContentFolderStatus[]>('list_content_folders', { root: null })
 
-  console.log('Symbolic link updated and explorer path refreshed.')
+export const inspectContentFolder = async (kind: ContentFolder) =>
+  invoke<ContentFolderStatus>('inspect_content_folder', { kind, root: null })
+
+export async function linkContentFolder(
+  kind: ContentFolder,
+  newFolder: string,
+): Promise<string | null> {
+  const backup = await invoke<string | null>('link_content_folder', {
+    kind,
+    newFolder,
+    root: null,
+  })
+  await refreshAfterLinkChange(kind, newFolder)
+  console.log(`[symlinkService] ${kind} link updated and explorer path refreshed.`)
+  return backup
 }
+
+export async function relocateContentFolder(
+  kind: ContentFolder,
+  newFolder: string,
+  mode: RelocateMode,
+): Promise<RelocationResult> {
+  const result = await invoke<RelocationResult>('relocate_content_folder', {
+    kind,
+    newFolder,
+    mode,
+    root: null,
+  })
+  await refreshAfterLinkChange(kind, newFolder)
+  return result
+}
+
+export async function restoreContentFolder(
+  kind: ContentFolder,
+): Promise<ContentFolderStatus> {
+  const status = await invoke<ContentFolderStatus>('restore_content_folder', {
+    kind,
+    root: null,
+  })
+  await refreshAfterLinkChange(kind, status.path)
+  return status
+}
+
+export const checkContentFolderLinks = async () =>
+  invoke<LinkHealth[]>('check_content_folder_links', { root: null })
+
+export async function repairContentFolderLink(
+  kind: ContentFolder,
+  repair: RepairAction,
+): Promise<LinkHealth> {
+  const health = await invoke<LinkHealth>('repair_content_folder_link', {
+    kind,
+    repair,
+    root: null,
+  })
+  await refreshAfterLinkChange(kind, health.linkTarget ?? health.path)
+  return health
+}
+
+export const listContentBackups = async (kind: ContentFolder) =>
+  invoke<BackupEntry[]>('list_content_backups', { kind, root: null })
+
+export async function restoreContentBackup(
+  kind: ContentFolder,
+  backupPath: string,
+): Promise<ContentFolderStatus> {
+  const status = await invoke<ContentFolderStatus>('restore_content_backup', {
+    kind,
+    backupPath,
+    root: null,
+  })
+  await refreshAfterLinkChange(kind, status.path)
+  return status
+}
+
+export const mergeContentBackup = async (kind: ContentFolder, backupPath: string) =>
+  invoke<BackupMergeResult>('merge_content_backup', {
+    kind,
+    backupPath,
+    root: null,
+  })
+
+export const trashContentBackup = async (kind: ContentFolder, backupPath: string) =>
+  invoke<void>('trash_content_backup', { kind, backupPath, root: null })
+
+export const updateMapsSymlink = (newFolder: string) =>
+  linkContentFolder('maps', newFolder)
+
+export const relocateMapsFolder = (newFolder: string, mode: RelocateMode) =>
+  relocateContentFolder('maps', newFolder, mode)
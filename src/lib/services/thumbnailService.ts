@@ -0,0 +1,38 @@
+import { invoke } from '@tauri-apps/api/core'
+import { listen } from '@tauri-apps/api/event'
+import type {
+  CacheUsage,
+  ContentFolder,
+  FsEntry,
+  ThumbnailReady,
+} from '$lib/types/fsTypes'
+import { normalizePath } from './pathService'
+
+export const getThumbnailCacheUsage = async () =>
+  invoke<CacheUsage>('get_thumbnail_cache_usage')
+
+/** Clears one kind's thumbnails, or the whole cache when `kind` is omitted. */
+export const clearThumbnailCache = async (kind?: ContentFolder) =>
+  invoke<CacheUsage>('clear_thumbnail_cache', { kind: kind ?? null })
+
+export const onThumbnailReady = (callback: (ready: ThumbnailReady) => void) =>
+  listen<ThumbnailReady>('thumbnail-ready', (evt) => callback(evt.payload))
+
+/** Applies a finished render to the entry it belongs to; failures change nothing. */
+export const patchThumbnail = (
+  entries: FsEntry[],
+  ready: ThumbnailReady,
+): FsEntry[] => {
+  if (!ready.thumbnailPath) return entries
+  const item = normalizePath(ready.item)
+  const index = entries.findIndex((e) => normalizePath(e.path) === item)
+  if (index === -1) return entries
+  const entry = { ...entries[index] }
+  if (ready.size === 'small') {
+    entry.thumbnailPath = ready.thumbnailPath
+    entry.thumbnailMimeType = ready.mimeType
+  } else {
+    entry.thumbnailLargePath = ready.thumbnailPath
+  }
+  return entries.map((e, i) => (i === index ? entry : e))
+}
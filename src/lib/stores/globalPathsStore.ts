@@ -1,6 +1,7 @@
 // src/lib/stores/globalPathsStore.ts
 import { writable, derived, get } from 'svelte/store'
 import { findSkaterXlPath } from '$lib/services/pathService';
+import { updateSettings } from '$lib/services/settingsService';
 
 let _defaultMapsPath = '';
 let _defaultModsPath = '';
@@ -71,6 +72,7 @@ export async function initializeExplorerPaths() {
 export function setSkaterXLGamePath(path: string) {
   skaterXLGamePath.set(path)
   modsDirectory.set(`${path}/Mods`)
+  updateSettings({ gameInstallPath: path }).catch(console.error)
 }
 
 // Watchers to persist changes
@@ -79,9 +81,11 @@ mapsDirectory.subscribe((val) => {
   if (val.trim() !== '' && val !== _defaultMapsPath) {
     localStorage.setItem('customMapsDirectory', val)
     console.log('[GlobalPathsStore] Saved custom mapsDirectory:', val)
+    updateSettings({ mapsPath: val }).catch(console.error)
   } else {
     localStorage.removeItem('customMapsDirectory')
     console.log('[GlobalPathsStore] Reset to default mapsDirectory')
+    updateSettings({ mapsPath: null }).catch(console.error)
   }
 })
 
@@ -90,8 +94,10 @@ modsDirectory.subscribe((val) => {
   if (val.trim() !== '' && val !== _defaultModsPath) {
     localStorage.setItem('customModsDirectory', val)
     console.log('[GlobalPathsStore] Saved custom modsDirectory:', val)
+    updateSettings({ modsPath: val }).catch(console.error)
   } else {
     localStorage.removeItem('customModsDirectory')
     console.log('[GlobalPathsStore] Reset to default modsDirectory')
+    updateSettings({ modsPath: null }).catch(console.error)
   }
 })
@@ -2,11 +2,15 @@ import { writable, get } from 'svelte/store'
 import { browser } from '$app/environment'
 import { listen } from '@tauri-apps/api/event'
 import { mapsDirectory } from './globalPathsStore'
-import { loadLocalMaps } from '$lib/services/fileService'
+import { loadLocalMaps, syncModioCatalog } from '$lib/services/fileService'
 import { normalizePath } from '$lib/services/pathService'
 import { handleError } from '$lib/utils/errorHandler'
 import { modioMapsSearchIndex } from '$lib/utils/flexSearchUtils'
 import { fetchAllMaps } from '$lib/services/modioCacheService'
+import {
+  onThumbnailReady,
+  patchThumbnail,
+} from '$lib/services/thumbnailService'
 import type { FsEntry, DirectoryListingResult } from '$lib/types/fsTypes'
 import type { Mod } from '$lib/types/modioTypes'
 
@@ -96,6 +100,12 @@ if (browser && !hasInitialized) {
     }
     _loadLocal(dir, 'rust-fs-change').catch(console.error)
   }).catch(console.error)
+
+  // Listings return before queued thumbnails are rendered.
+  onThumbnailReady((ready) => {
+    if (ready.kind !== 'maps') return
+    localMaps.update((entries) => patchThumbnail(entries, ready))
+  }).catch(console.error)
 }
 
 export const modioMaps = writable<Mod[]>([])
@@ -112,6 +122,8 @@ export async function refreshModioMaps() {
     if (browser) {
       modioMapsSearchIndex.clear()
       modioMapsSearchIndex.add(mods)
+      await syncModioCatalog(mods)
+      await refreshLocalMaps()
     }
   } catch (e: any) {
     handleError(e, '[mapsStore] refreshModioMaps')
@@ -0,0 +1,68 @@
+import { writable, get } from 'svelte/store'
+import { browser } from '$app/environment'
+import { listen } from '@tauri-apps/api/event'
+import { modsDirectory } from './globalPathsStore'
+import { loadLocalMods } from '$lib/services/fileService'
+import { normalizePath } from '$lib/services/pathService'
+import {
+  onThumbnailReady,
+  patchThumbnail,
+} from '$lib/services/thumbnailService'
+import { handleError } from '$lib/utils/errorHandler'
+import type { FsEntry } from '$lib/types/fsTypes'
+
+export const localMods = writable<FsEntry[]>([])
+export const localModsLoading = writable(false)
+export const localModsError = writable<string | null>(null)
+
+let isLoadingLocal = false
+let lastDir = ''
+let hasInitialized = false
+
+async function _loadLocal(dir: string, source = 'unknown') {
+  console.log(`[modsStore] Loading local mods from ${source}:`, dir)
+  if (isLoadingLocal) return
+  isLoadingLocal = true
+  localModsLoading.set(true)
+  localModsError.set(null)
+  try {
+    const res = await loadLocalMods(normalizePath(dir))
+    localMods.set(res.entries)
+  } catch (e: any) {
+    handleError(e, '[modsStore] refreshLocalMods')
+    localModsError.set(e.message ?? String(e))
+    localMods.set([])
+  } finally {
+    localModsLoading.set(false)
+    isLoadingLocal = false
+  }
+}
+
+export async function refreshLocalMods() {
+  const dir = get(modsDirectory).trim()
+  if (!dir) return
+  await _loadLocal(dir, 'manual')
+}
+
+if (browser && !hasInitialized) {
+  hasInitialized = true
+
+  modsDirectory.subscribe((d) => {
+    const dir = d.trim()
+    if (!dir || dir === lastDir || isLoadingLocal) return
+    lastDir = dir
+    _loadLocal(dir, 'modsDirectory.subscribe').catch(console.error)
+  })
+
+  listen<{ path: string; kind: string }>('rust-fs-change', (evt) => {
+    const dir = get(modsDirectory)
+    if (!dir || !evt.payload.path.startsWith(normalizePath(dir))) return
+    _loadLocal(dir, 'rust-fs-change').catch(console.error)
+  }).catch(console.error)
+
+  // Listings return before queued thumbnails are rendered.
+  onThumbnailReady((ready) => {
+    if (ready.kind !== 'mods') return
+    localMods.update((entries) => patchThumbnail(entries, ready))
+  }).catch(console.error)
+}
@@ -4,6 +4,28 @@ export enum ListingStatus {
   DoesNotExist = 'doesNotExist',
 }
 
+export type MatchMethod =
+  | 'installRecord'
+  | 'metadata'
+  | 'filename'
+  | 'nameSimilarity'
+
+export interface ModioMatch {
+  modId: number
+  confidence: number
+  method: MatchMethod
+}
+
+export interface MapMetadata {
+  author?: string | null
+  modioId?: number | null
+  version?: string | null
+  description?: string | null
+  tags: string[]
+  installedAt?: number | null
+  userRating?: number | null
+}
+
 export interface FsEntry {
   name: string | null
   path: string
@@ -12,6 +34,14 @@ export interface FsEntry {
   modified: number | null
   thumbnailPath: string | null
   thumbnailMimeType: string | null
+  /** 640x360 render; the small one is `thumbnailPath`. */
+  thumbnailLargePath?: string | null
+  modioMatch?: ModioMatch
+  metadata?: MapMetadata
+  enabled?: boolean
+  libraryRoot?: string
+  modInfo?: ModInfo
+  manifestStatus?: ManifestStatus
 }
 
 export interface DirectoryListingResult {
@@ -26,3 +56,155 @@ export interface InstallationResult {
   final_path?: string
   source: string
 }
+
+export type RelocateMode = 'move' | 'copy'
+
+export interface RelocationProgress {
+  phase: string
+  bytesDone: number
+  bytesTotal: number
+  filesDone: number
+  filesTotal: number
+  current?: string
+}
+
+export interface RelocationResult {
+  newLocation: string
+  filesTransferred: number
+  bytesTransferred: number
+  backupPath?: string
+}
+
+export type ContentFolder = 'maps' | 'mods' | 'gear' | 'replays' | 'templates'
+
+export interface ContentFolderStatus {
+  kind: ContentFolder
+  path: string
+  exists: boolean
+  isLink: boolean
+  linkTarget?: string
+}
+
+export type LinkState = 'notLinked' | 'missing' | 'healthy' | 'dangling'
+
+export interface LinkHealth {
+  kind: ContentFolder
+  path: string
+  state: LinkState
+  linkTarget?: string
+  targetExists: boolean
+  targetWritable: boolean
+  freeSpaceBytes?: number
+  newestBackup?: string
+}
+
+export type RepairAction =
+  | { action: 'repoint'; newTarget: string }
+  | { action: 'restoreBackup' }
+
+export interface BackupEntry {
+  kind: ContentFolder
+  name: string
+  path: string
+  sizeBytes: number
+  fileCount: number
+  modified?: number
+}
+
+export interface BackupMergeResult {
+  target: string
+  merged: string[]
+  skipped: string[]
+  backupRemoved: boolean
+}
+
+export interface GameInstallation {
+  installDir: string | null
+  steamLibrary: string | null
+  manifestPath: string | null
+  buildId: number | null
+  lastUpdated: number | null
+  userDataPath: string | null
+}
+
+export type CandidateSource =
+  | 'documents'
+  | 'steamLibrary'
+  | 'steamDefault'
+  | 'flatpak'
+  | 'snap'
+  | 'compatDataEnv'
+  | 'customPrefix'
+
+export interface UserDataCandidate {
+  path: string
+  source: CandidateSource
+  origin: string
+}
+
+export interface SteamLibrary {
+  path: string
+  source: CandidateSource
+}
+
+export type CandidateRejection =
+  | 'missing'
+  | 'notADirectory'
+  | 'duplicate'
+  | 'notChosen'
+
+export interface CheckedCandidate extends UserDataCandidate {
+  rejection?: CandidateRejection
+}
+
+export interface DiscoveryReport {
+  steamLibraries: SteamLibrary[]
+  candidates: CheckedCandidate[]
+  chosen: UserDataCandidate | null
+  warnings: string[]
+}
+
+export interface ModInfo {
+  id: string
+  displayName?: string
+  author?: string
+  version?: string
+  managerVersion?: string
+  gameVersion?: string
+  requirements: string[]
+  loadAfter: string[]
+  assemblyName?: string
+  entryMethod?: string
+  homePage?: string
+}
+
+export type ManifestStatus =
+  | { status: 'valid' }
+  | { status: 'missing' }
+  | { status: 'malformed'; message: string }
+
+export interface KindUsage {
+  kind: ContentFolder
+  bytes: number
+  files: number
+}
+
+export interface CacheUsage {
+  path: string
+  totalBytes: number
+  files: number
+  capBytes: number
+  byKind: KindUsage[]
+}
+
+export type ThumbnailSize = 'small' | 'large'
+
+/** Payload of `thumbnail-ready`, emitted when a queued render finishes. */
+export interface ThumbnailReady {
+  kind: ContentFolder
+  item: string
+  size: ThumbnailSize
+  thumbnailPath: string | null
+  mimeType: string | null
+  error?: string
+}
@@ -0,0 +1,70 @@
+export type IssueSeverity = 'error' | 'warning'
+
+export type CompatIssueKind =
+  | { kind: 'missingManifest' }
+  | { kind: 'malformedManifest'; message: string }
+  | { kind: 'missingRequirement'; requiredId: string }
+  | {
+      kind: 'outdatedRequirement'
+      requiredId: string
+      requiredVersion: string
+      installedVersion: string
+    }
+  | { kind: 'gameVersion'; required: string; detected: string }
+  | { kind: 'managerVersion'; required: string; installed: string }
+  | { kind: 'duplicateId'; folders: string[] }
+
+export type CompatIssue = CompatIssueKind & {
+  severity: IssueSeverity
+  folder: string
+  modId?: string
+}
+
+export interface CompatibilityReport {
+  modsPath: string
+  modsChecked: number
+  gameVersion: string | null
+  gameBuildId: number | null
+  managerVersion: string | null
+  issues: CompatIssue[]
+}
+
+export type ToggleMethod = 'params' | 'disabledStore'
+
+export interface ModToggleResult {
+  path: string
+  enabled: boolean
+  method: ToggleMethod
+}
+
+export type LoaderKind = 'unityModManager' | 'bepInEx'
+
+export interface LoaderStatus {
+  kind: LoaderKind
+  installed: boolean
+  version: string | null
+  path: string
+  managed: boolean
+}
+
+export type DllSource = 'mods' | 'managed'
+
+export interface AssemblyCopy {
+  path: string
+  version: string
+  source: DllSource
+  modFolder: string | null
+}
+
+export interface AssemblyConflict {
+  name: string
+  versions: string[]
+  copies: AssemblyCopy[]
+}
+
+export interface DllConflictReport {
+  modsPath: string
+  managedPath: string | null
+  assembliesScanned: number
+  conflicts: AssemblyConflict[]
+}
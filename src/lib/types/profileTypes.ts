@@ -0,0 +1,31 @@
+export interface MapProfile {
+  name: string
+  maps: string[]
+}
+
+export interface MapProfilesConfig {
+  libraryPath: string | null
+  profiles: MapProfile[]
+  activeProfile: string | null
+}
+
+export interface ProfileActivationResult {
+  profilePath: string
+  linked: number
+  missing: string[]
+}
+
+export interface LibraryMapRef {
+  root: string
+  name: string
+}
+
+export interface MaterializeResult {
+  mapsPath: string
+  linked: string[]
+  removed: string[]
+  missing: string[]
+  conflicts: string[]
+  /** Maps that could not be linked or unlinked, as `name: reason`. */
+  errors: string[]
+}
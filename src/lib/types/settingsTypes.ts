@@ -0,0 +1,28 @@
+export interface InstallPolicy {
+  writeSidecars: boolean
+  recordInstalls: boolean
+}
+
+export interface Limits {
+  thumbnailCacheMb: number
+  thumbnailWorkers: number
+}
+
+export interface Settings {
+  gamePath: string | null
+  gameInstallPath: string | null
+  gameVersion: string | null
+  mapsPath: string | null
+  modsPath: string | null
+  customPrefixes: string[]
+  install: InstallPolicy
+  limits: Limits
+}
+
+/** Partial update; nested objects are merged key by key on the Rust side. */
+export type SettingsPatch = Partial<
+  Omit<Settings, 'install' | 'limits'> & {
+    install: Partial<InstallPolicy>
+    limits: Partial<Limits>
+  }
+>